@@ -0,0 +1,557 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+//! Conversions between `Value` and the native rust types
+//! `Value` 与 rust 原生类型之间的转换
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+use crate::double::Double;
+use crate::types::{NList, NMap, NSet, NullType, Value};
+
+/// Error of converting `Value` into the rust type
+/// 将 `Value` 转换为 rust 类型的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertError {
+    /// The value is not the expected variant
+    TypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// The number doesn't fit in the target type
+    OutOfRange { value: String, target: &'static str },
+    /// The string is not in UTF-8
+    InvalidUtf8,
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::TypeMismatch { expected, actual } => {
+                write!(f, "expected value `{}`, but got `{}`", expected, actual)
+            }
+            ConvertError::OutOfRange { value, target } => {
+                write!(f, "value {} is out of range of `{}`", value, target)
+            }
+            ConvertError::InvalidUtf8 => write!(f, "string value is not in UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+impl Value {
+    /// The name of the variant, e.g. `iVal`
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Value::nVal(_) => "nVal",
+            Value::bVal(_) => "bVal",
+            Value::iVal(_) => "iVal",
+            Value::fVal(_) => "fVal",
+            Value::sVal(_) => "sVal",
+            Value::dVal(_) => "dVal",
+            Value::tVal(_) => "tVal",
+            Value::dtVal(_) => "dtVal",
+            Value::vVal(_) => "vVal",
+            Value::eVal(_) => "eVal",
+            Value::pVal(_) => "pVal",
+            Value::lVal(_) => "lVal",
+            Value::mVal(_) => "mVal",
+            Value::uVal(_) => "uVal",
+            Value::gVal(_) => "gVal",
+            Value::UnknownField(_) => "UnknownField",
+        }
+    }
+
+    /// Whether it's the null value
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::nVal(_))
+    }
+}
+
+#[inline]
+fn mismatch(expected: &'static str, actual: &Value) -> ConvertError {
+    ConvertError::TypeMismatch {
+        expected,
+        actual: actual.variant_name(),
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::iVal(i) => Ok(i),
+            other => Err(mismatch("iVal", &other)),
+        }
+    }
+}
+
+macro_rules! impl_try_from_value_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = ConvertError;
+
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    let i = i64::try_from(value)?;
+                    <$t>::try_from(i).map_err(|_| ConvertError::OutOfRange {
+                        value: i.to_string(),
+                        target: stringify!($t),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+// u8 is left out, so `Vec<u8>` is always the bytes of `sVal`
+impl_try_from_value_for_int!(i32, i16, i8, u64, u32, u16);
+
+impl TryFrom<Value> for bool {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::bVal(b) => Ok(b),
+            other => Err(mismatch("bVal", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::fVal(f) => Ok(f.0),
+            other => Err(mismatch("fVal", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        // NaN and infinity are kept, only the finite value too large for f32 is rejected
+        let f = f64::try_from(value)?;
+        let narrowed = f as f32;
+        if f.is_finite() && !narrowed.is_finite() {
+            return Err(ConvertError::OutOfRange {
+                value: f.to_string(),
+                target: "f32",
+            });
+        }
+        Ok(narrowed)
+    }
+}
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::sVal(s) => Ok(s),
+            other => Err(mismatch("sVal", &other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        String::from_utf8(Vec::<u8>::try_from(value)?).map_err(|_| ConvertError::InvalidUtf8)
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value, Error = ConvertError>,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::lVal(list) => list.values.into_iter().map(T::try_from).collect(),
+            other => Err(mismatch("lVal", &other)),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for HashMap<String, T>
+where
+    T: TryFrom<Value, Error = ConvertError>,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::mVal(map) => map
+                .kvs
+                .into_iter()
+                .map(|(k, v)| {
+                    let k = String::from_utf8(k).map_err(|_| ConvertError::InvalidUtf8)?;
+                    Ok((k, T::try_from(v)?))
+                })
+                .collect(),
+            other => Err(mismatch("mVal", &other)),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for HashSet<T>
+where
+    T: TryFrom<Value, Error = ConvertError> + Eq + Hash,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::uVal(set) => set.values.into_iter().map(T::try_from).collect(),
+            other => Err(mismatch("uVal", &other)),
+        }
+    }
+}
+
+// A generic `Option<T>` would overlap `TryFrom<U> for T where U: Into<T>` at `Option<Value>`
+macro_rules! impl_try_from_value_for_option {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Value> for Option<$t> {
+                type Error = ConvertError;
+
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    match value {
+                        Value::nVal(_) => Ok(None),
+                        other => <$t>::try_from(other).map(Some),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_value_for_option!(i64, i32, i16, i8, u64, u32, u16, bool, f64, f32, String, Vec<u8>);
+
+impl<T> TryFrom<Value> for Option<Vec<T>>
+where
+    T: TryFrom<Value, Error = ConvertError>,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::nVal(_) => Ok(None),
+            other => Vec::<T>::try_from(other).map(Some),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Option<HashMap<String, T>>
+where
+    T: TryFrom<Value, Error = ConvertError>,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::nVal(_) => Ok(None),
+            other => HashMap::<String, T>::try_from(other).map(Some),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Option<HashSet<T>>
+where
+    T: TryFrom<Value, Error = ConvertError> + Eq + Hash,
+{
+    type Error = ConvertError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::nVal(_) => Ok(None),
+            other => HashSet::<T>::try_from(other).map(Some),
+        }
+    }
+}
+
+macro_rules! impl_value_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                #[inline]
+                fn from(i: $t) -> Self {
+                    Value::iVal(i as i64)
+                }
+            }
+        )*
+    };
+}
+
+// u64 is left out, the value above `i64::MAX` doesn't fit in `iVal`, see `TryFrom<u64>`
+impl_value_from_int!(i64, i32, i16, i8, u32, u16);
+
+impl TryFrom<u64> for Value {
+    type Error = ConvertError;
+
+    fn try_from(u: u64) -> Result<Self, Self::Error> {
+        i64::try_from(u)
+            .map(Value::iVal)
+            .map_err(|_| ConvertError::OutOfRange {
+                value: u.to_string(),
+                target: "i64",
+            })
+    }
+}
+
+impl From<bool> for Value {
+    #[inline]
+    fn from(b: bool) -> Self {
+        Value::bVal(b)
+    }
+}
+
+impl From<f64> for Value {
+    #[inline]
+    fn from(f: f64) -> Self {
+        Value::fVal(Double(f))
+    }
+}
+
+// Widening f32 to f64 is exact
+impl From<f32> for Value {
+    #[inline]
+    fn from(f: f32) -> Self {
+        Value::fVal(Double(f as f64))
+    }
+}
+
+impl From<String> for Value {
+    #[inline]
+    fn from(s: String) -> Self {
+        Value::sVal(s.into_bytes())
+    }
+}
+
+impl From<&str> for Value {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Value::sVal(s.as_bytes().to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        Value::sVal(bytes)
+    }
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Value::nVal(NullType::__NULL__),
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(values: Vec<T>) -> Self {
+        Value::lVal(Box::new(NList {
+            values: values.into_iter().map(Into::into).collect(),
+        }))
+    }
+}
+
+impl<T> From<HashMap<String, T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(kvs: HashMap<String, T>) -> Self {
+        Value::mVal(Box::new(NMap {
+            kvs: kvs
+                .into_iter()
+                .map(|(k, v)| (k.into_bytes(), v.into()))
+                .collect::<BTreeMap<_, _>>(),
+        }))
+    }
+}
+
+impl<T> From<HashSet<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(values: HashSet<T>) -> Self {
+        Value::uVal(Box::new(NSet {
+            values: values.into_iter().map(Into::into).collect::<BTreeSet<_>>(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T>(value: T)
+    where
+        T: Into<Value> + TryFrom<Value, Error = ConvertError> + Clone + PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(T::try_from(value.clone().into()), Ok(value));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(i64::MIN);
+        round_trip(-7i32);
+        round_trip(-7i16);
+        round_trip(-7i8);
+        round_trip(7u32);
+        round_trip(7u16);
+        round_trip(true);
+        round_trip(1.5f64);
+        round_trip(1.5f32);
+        round_trip(String::from("中文"));
+        round_trip(b"\xff".to_vec());
+        round_trip(vec![1i64, 2, 3]);
+        round_trip(vec![String::from("a"), String::from("b")]);
+        round_trip(HashMap::from([(String::from("a"), 1i64), (String::from("b"), 2)]));
+        round_trip(HashSet::from([1i64, 2]));
+        round_trip(Some(1i64));
+        round_trip(None::<String>);
+        round_trip(Some(vec![1i64]));
+        round_trip(None::<HashMap<String, i64>>);
+        round_trip(Some(HashSet::from([String::from("a")])));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            i8::try_from(Value::iVal(128)),
+            Err(ConvertError::OutOfRange {
+                value: String::from("128"),
+                target: "i8"
+            })
+        );
+        assert_eq!(i8::try_from(Value::iVal(-128)), Ok(-128));
+        assert_eq!(
+            u16::try_from(Value::iVal(-1)),
+            Err(ConvertError::OutOfRange {
+                value: String::from("-1"),
+                target: "u16"
+            })
+        );
+        assert_eq!(
+            i32::try_from(Value::iVal(i64::MAX)),
+            Err(ConvertError::OutOfRange {
+                value: i64::MAX.to_string(),
+                target: "i32"
+            })
+        );
+        assert_eq!(u64::try_from(Value::iVal(i64::MAX)), Ok(i64::MAX as u64));
+        assert_eq!(
+            u64::try_from(Value::iVal(-1)),
+            Err(ConvertError::OutOfRange {
+                value: String::from("-1"),
+                target: "u64"
+            })
+        );
+        assert_eq!(Value::try_from(i64::MAX as u64), Ok(Value::iVal(i64::MAX)));
+        assert_eq!(
+            Value::try_from(u64::MAX),
+            Err(ConvertError::OutOfRange {
+                value: u64::MAX.to_string(),
+                target: "i64"
+            })
+        );
+        assert_eq!(
+            f32::try_from(Value::from(f64::MAX)),
+            Err(ConvertError::OutOfRange {
+                value: f64::MAX.to_string(),
+                target: "f32"
+            })
+        );
+        assert_eq!(f32::try_from(Value::from(f64::INFINITY)), Ok(f32::INFINITY));
+        assert!(f32::try_from(Value::from(f64::NAN)).unwrap().is_nan());
+        assert!(matches!(
+            Vec::<i8>::try_from(Value::from(vec![1i64, 1000])),
+            Err(ConvertError::OutOfRange { ref value, .. }) if value == "1000"
+        ));
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        assert_eq!(
+            i64::try_from(Value::from("1")),
+            Err(ConvertError::TypeMismatch {
+                expected: "iVal",
+                actual: "sVal"
+            })
+        );
+        assert_eq!(
+            bool::try_from(Value::iVal(1)),
+            Err(ConvertError::TypeMismatch {
+                expected: "bVal",
+                actual: "iVal"
+            })
+        );
+        assert_eq!(
+            f64::try_from(Value::iVal(1)),
+            Err(ConvertError::TypeMismatch {
+                expected: "fVal",
+                actual: "iVal"
+            })
+        );
+        assert_eq!(
+            String::try_from(Value::nVal(NullType::__NULL__)),
+            Err(ConvertError::TypeMismatch {
+                expected: "sVal",
+                actual: "nVal"
+            })
+        );
+        assert_eq!(
+            Vec::<i64>::try_from(Value::from(HashSet::from([1i64]))),
+            Err(ConvertError::TypeMismatch {
+                expected: "lVal",
+                actual: "uVal"
+            })
+        );
+        assert_eq!(
+            Option::<i64>::try_from(Value::bVal(true)),
+            Err(ConvertError::TypeMismatch {
+                expected: "iVal",
+                actual: "bVal"
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        assert_eq!(
+            String::try_from(Value::sVal(b"\xff".to_vec())),
+            Err(ConvertError::InvalidUtf8)
+        );
+        let map = Value::mVal(Box::new(NMap {
+            kvs: BTreeMap::from([(b"\xff".to_vec(), Value::iVal(1))]),
+        }));
+        assert_eq!(
+            HashMap::<String, i64>::try_from(map),
+            Err(ConvertError::InvalidUtf8)
+        );
+    }
+}
@@ -2870,3 +2870,4 @@ pub mod errors {
 }
 
 pub mod double;
+pub mod convert;
@@ -71,6 +71,18 @@ pub mod types {
     }
 
     impl ExecutionResponse{
+        /// Collect the string values of the first column, e.g. the names returned by `SHOW TAGS`
+        pub fn get_sVal(&self) -> ::std::option::Option<::std::vec::Vec<::std::string::String>>{
+            let data = self.data.as_ref()?;
+            let mut res = ::std::vec::Vec::new();
+            for row in &data.rows{
+                if let ::std::option::Option::Some(common::types::Value::sVal(s)) = row.values.first(){
+                    res.push(::std::string::String::from_utf8_lossy(s).to_string());
+                }
+            }
+            ::std::option::Option::Some(res)
+        }
+
         pub fn show_data(&self){
             if let common::types::ErrorCode(0i32) = self.error_code{
             }else{
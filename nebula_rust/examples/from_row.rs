@@ -0,0 +1,40 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use nebula_rust::graph_client::connection_pool;
+use nebula_rust::value::from_row::FromRow;
+
+#[derive(Debug, FromRow)]
+struct Player {
+    name: String,
+    #[nebula(rename = "player_age")]
+    age: i64,
+    #[nebula(optional)]
+    team: Option<String>,
+    #[nebula(default)]
+    tags: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let address = "root:root@127.0.0.1:9669/basketballplayer";
+
+    let pool = connection_pool::ConnectionPool_nebula::new_pool(address).unwrap();
+
+    let session = pool.get_session(true).await.unwrap();
+    let players: Vec<Player> = session
+        .query("MATCH (v:player) RETURN v.name AS name, v.age AS player_age LIMIT 10;")
+        .await
+        .unwrap()
+        .into_typed()
+        .unwrap();
+    for player in players {
+        println!(
+            "{} {} {:?} {:?}",
+            player.name, player.age, player.team, player.tags
+        );
+    }
+}
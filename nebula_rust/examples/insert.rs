@@ -6,14 +6,15 @@
 
 use std::collections::HashMap;
 
-use nebula_rust::graph_client::{nebula_schema::{ColType, Tag, DataType, InsertTagQuery, InsertEdgeQueryWithRank},connection_pool,session};
+use nebula_rust::common::types::Value;
+use nebula_rust::graph_client::{nebula_schema::{ColType, Tag, DataType, InsertTagQuery, InsertEdgeQueryWithRank, BatchLimit},connection_pool,session};
 use rand::Rng;
 
 #[tokio::main]
 async fn main() {
    let address = "root:root@49.52.27.117:9669/testGraph";
 
-    let pool = connection_pool::ConnectionPool_nebula::new_pool(address);
+    let pool = connection_pool::ConnectionPool_nebula::new_pool(address).unwrap();
     
     pool.create_new_connection().await;
 
@@ -37,7 +38,7 @@ async fn main() {
 
     let session = pool.get_session(true).await.unwrap();
 
-    session.show_spaces().await;
+    session.show_spaces().await.unwrap();
     let space_name="testGraph";
     
     //insert tags
@@ -62,35 +63,37 @@ async fn main() {
         }else{
             mp.insert(i,0);
         }
-        let mut properties: HashMap<String, String> = HashMap::new();
-        properties.insert("id".to_string(), "\"".to_string()+address.as_str()+"\"");
+        let mut properties: HashMap<String, Value> = HashMap::new();
+        properties.insert("id".to_string(), Value::from(address.as_str()));
         let vid=address;
         let tag_name=String::from("user");
         let insert_tag_query=InsertTagQuery::new(String::from(space_name.clone()), tag_name, properties, vid);
         insert_tag_queries.push(insert_tag_query);
     }
-    let mut properties: HashMap<String, String> = HashMap::new();
-    properties.insert("id".to_string(), "\"".to_string()+"out"+"\"");
+    let mut properties: HashMap<String, Value> = HashMap::new();
+    properties.insert("id".to_string(), Value::from("out"));
     let vid=String::from("out");
     let tag_name=String::from("user");
     let insert_tag_query=InsertTagQuery::new(String::from(space_name.clone()), tag_name, properties, vid);
     insert_tag_queries.push(insert_tag_query);
-    session.insert_tags(insert_tag_queries).await;
+    for batch in session.insert_tags_batched(&insert_tag_queries, true, &BatchLimit::default()).await.unwrap() {
+        batch.result.unwrap();
+    }
 
-    fn get_transfer(x:i32, y:i32, mp:&mut HashMap<i32,i32>,addresses:&mut Vec<String>, space_name:&str,block:String) -> InsertEdgeQueryWithRank{
+    fn get_transfer(x:i32, y:i32, mp:&mut HashMap<i32,i32>,addresses:&mut Vec<String>, space_name:&str,block:i64) -> InsertEdgeQueryWithRank{
         let a=mp.get(&x).clone();
         let v=get_random_int(1, *a.unwrap());
         let mut from=mp.get_mut(&x);
         (*from.unwrap())-=v;
         let mut to=mp.get_mut(&y);
         (*to.unwrap())+=v;
-        let mut properties:HashMap<String, String>=HashMap::new();
+        let mut properties:HashMap<String, Value>=HashMap::new();
         let ax=addresses.get((x-1) as usize).unwrap().clone();
         let bx=addresses.get((y-1) as usize).unwrap().clone();
-        properties.insert("from_account".to_string(),"\"".to_string()+ax.as_str()+"\"");
-        properties.insert("to_account".to_string(),"\"".to_string()+bx.as_str()+"\"");
-        properties.insert("value".to_string(),"\"".to_string()+v.to_string().as_str()+"\"");
-        properties.insert("block".to_string(),block);
+        properties.insert("from_account".to_string(),Value::from(ax.as_str()));
+        properties.insert("to_account".to_string(),Value::from(bx.as_str()));
+        properties.insert("value".to_string(),Value::from(v.to_string().as_str()));
+        properties.insert("block".to_string(),Value::from(block));
         let insert_edge_query = InsertEdgeQueryWithRank::new(
             String::from(space_name.clone()),
             "tx".to_string(),
@@ -101,7 +104,7 @@ async fn main() {
         );
         insert_edge_query
     }
-    fn get_multitransfer(x:i32, y:i32, z:i32,mp:&mut HashMap<i32,i32>,addresses:&mut Vec<String>, space_name:&str,block:String, s:&mut Vec<InsertEdgeQueryWithRank>){
+    fn get_multitransfer(x:i32, y:i32, z:i32,mp:&mut HashMap<i32,i32>,addresses:&mut Vec<String>, space_name:&str,block:i64, s:&mut Vec<InsertEdgeQueryWithRank>){
         let a=mp.get(&x).clone();
         let v=get_random_int(1, (*a.unwrap()).clone());
         let vv=get_random_int(1, std::cmp::max(v,v-1));
@@ -116,11 +119,11 @@ async fn main() {
         let bx=addresses.get((y-1) as usize).unwrap().clone();
         let cx=addresses.get((z-1) as usize).unwrap().clone();
         
-            let mut properties1:HashMap<String, String>=HashMap::new();
-            properties1.insert("from_account".to_string(),"\"".to_string()+ax.clone().as_str()+"\"");
-            properties1.insert("to_account".to_string(),"\"".to_string()+bx.clone().as_str()+"\"");
-            properties1.insert("value".to_string(),"\"".to_string()+(v-vv).to_string().as_str()+"\"");
-            properties1.insert("block".to_string(),block.clone());
+            let mut properties1:HashMap<String, Value>=HashMap::new();
+            properties1.insert("from_account".to_string(),Value::from(ax.clone().as_str()));
+            properties1.insert("to_account".to_string(),Value::from(bx.clone().as_str()));
+            properties1.insert("value".to_string(),Value::from((v-vv).to_string().as_str()));
+            properties1.insert("block".to_string(),Value::from(block));
             let insert_edge_query = InsertEdgeQueryWithRank::new(
                 String::from(space_name.clone()),
                 "tx".to_string(),
@@ -132,11 +135,11 @@ async fn main() {
             s.push(insert_edge_query);
         
         
-            let mut properties2:HashMap<String, String>=HashMap::new();
-            properties2.insert("from_account".to_string(),"\"".to_string()+ax.clone().as_str()+"\"");
-            properties2.insert("to_account".to_string(),"\"".to_string()+cx.clone().as_str()+"\"");
-            properties2.insert("value".to_string(),"\"".to_string()+vv.to_string().as_str()+"\"");
-            properties2.insert("block".to_string(),block.clone());
+            let mut properties2:HashMap<String, Value>=HashMap::new();
+            properties2.insert("from_account".to_string(),Value::from(ax.clone().as_str()));
+            properties2.insert("to_account".to_string(),Value::from(cx.clone().as_str()));
+            properties2.insert("value".to_string(),Value::from(vv.to_string().as_str()));
+            properties2.insert("block".to_string(),Value::from(block));
             let insert_edge_query = InsertEdgeQueryWithRank::new(
                 String::from(space_name.clone()),
                 "tx".to_string(),
@@ -148,11 +151,11 @@ async fn main() {
             s.push(insert_edge_query);
         
         
-            let mut properties3:HashMap<String, String>=HashMap::new();
-            properties3.insert("from_account".to_string(),"\"".to_string()+cx.clone().as_str()+"\"");
-            properties3.insert("to_account".to_string(),"\"".to_string()+"out"+"\"");
-            properties3.insert("value".to_string(),"\"".to_string()+outv.to_string().as_str()+"\"");
-            properties3.insert("block".to_string(),block.clone());
+            let mut properties3:HashMap<String, Value>=HashMap::new();
+            properties3.insert("from_account".to_string(),Value::from(cx.clone().as_str()));
+            properties3.insert("to_account".to_string(),Value::from("out"));
+            properties3.insert("value".to_string(),Value::from(outv.to_string().as_str()));
+            properties3.insert("block".to_string(),Value::from(block));
             let insert_edge_query = InsertEdgeQueryWithRank::new(
                 String::from(space_name.clone()),
                 "tx".to_string(),
@@ -182,7 +185,7 @@ async fn main() {
         if *c >=19{
             continue;
         }
-        let insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name, String::from("1"));
+        let insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name, 1);
         insert_edge_queries.push(insert_edge_query);
         //let c=countd.get_mut(&x);
         *c+=1;
@@ -190,7 +193,7 @@ async fn main() {
             
             continue;
         }
-        let insert_edge_query = get_transfer(x, z, &mut mp, &mut addresses, space_name, String::from("1"));
+        let insert_edge_query = get_transfer(x, z, &mut mp, &mut addresses, space_name, 1);
         *c+=1;
         insert_edge_queries.push(insert_edge_query);
     }
@@ -206,7 +209,7 @@ async fn main() {
         if *c >=19{
             continue;
         }
-        let insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name,String::from("2"));
+        let insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name,2);
         insert_edge_queries.push(insert_edge_query);
         *c+=1;
     }
@@ -218,7 +221,7 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        let mut insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name, String::from("3"));
+        let mut insert_edge_query = get_transfer(x, y, &mut mp, &mut addresses, space_name, 3);
         insert_edge_queries.push(insert_edge_query);
 
     }
@@ -234,12 +237,12 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("4"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 4,&mut insert_edge_queries);
         let a=mp.get(&x).clone();
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("5"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 5,&mut insert_edge_queries);
     }
     for i in 1..20{
         let x=get_random_int(46, 60);
@@ -252,7 +255,7 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("6"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 6,&mut insert_edge_queries);
     }
     for i in 1..10{
         let x=get_random_int(61, 70);
@@ -265,7 +268,7 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("7"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 7,&mut insert_edge_queries);
     }
     for i in 1..10{
         let x=get_random_int(71, 80);
@@ -278,7 +281,7 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("8"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 8,&mut insert_edge_queries);
     }
     for i in 1..10{
         let x=get_random_int(81, 90);
@@ -291,8 +294,10 @@ async fn main() {
         if *a.unwrap() <= 1{
             continue;
         }
-        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, String::from("9"),&mut insert_edge_queries);
+        get_multitransfer(x, y, z,&mut mp, &mut addresses, space_name, 9,&mut insert_edge_queries);
     }
     
-    session.insert_edges(insert_edge_queries).await;
+    for batch in session.insert_edges_batched(&insert_edge_queries, true, &BatchLimit::default()).await.unwrap() {
+        batch.result.unwrap();
+    }
 }
@@ -0,0 +1,80 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use nebula_rust::graph_client::connection_pool;
+use nebula_rust::graph_client::nebula_schema::{ColType, NebulaEdge, NebulaTag};
+use nebula_rust::graph_client::session::Session;
+
+#[derive(NebulaTag)]
+#[nebula(name = "player", comment = "basketball player")]
+struct Player {
+    #[nebula(vid)]
+    id: String,
+    name: String,
+    #[nebula(default_value = 18)]
+    age: i64,
+    nickname: Option<String>,
+}
+
+#[derive(NebulaEdge)]
+#[nebula(name = "follow")]
+struct Follow {
+    #[nebula(src)]
+    follower: String,
+    #[nebula(dst)]
+    followee: String,
+    degree: i64,
+}
+
+/// The storaged of other parts may load the new schema a little later than the probe of `wait_for_schema`
+async fn insert(session: &Session, statement: &str) {
+    for _ in 0..10 {
+        match session.query(statement).await {
+            Err(e) if e.is_schema_missing() => {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await
+            }
+            result => {
+                result.unwrap();
+                return;
+            }
+        }
+    }
+    panic!("the schema of `{}` isn't loaded", statement);
+}
+
+#[tokio::main]
+async fn main() {
+    let address = "root:root@127.0.0.1:9669/basketballplayer";
+
+    let pool = connection_pool::ConnectionPool_nebula::new_pool(address).unwrap();
+
+    let session = pool.get_session(true).await.unwrap();
+    session.query(&Player::create_statement().unwrap()).await.unwrap();
+    session.query(&Follow::create_statement().unwrap()).await.unwrap();
+
+    let player = Player {
+        id: "player100".to_string(),
+        name: "Tim Duncan".to_string(),
+        age: 42,
+        nickname: None,
+    };
+    let follow = Follow {
+        follower: "player101".to_string(),
+        followee: "player100".to_string(),
+        degree: 95,
+    };
+    let space_name = session.space();
+    session
+        .wait_for_schema(&space_name, Player::tag_name(), ColType::Tag, 10_000)
+        .await
+        .unwrap();
+    session
+        .wait_for_schema(&space_name, Follow::edge_name(), ColType::Edge, 10_000)
+        .await
+        .unwrap();
+    insert(&session, &player.insert_statement().unwrap()).await;
+    insert(&session, &follow.insert_statement().unwrap()).await;
+}
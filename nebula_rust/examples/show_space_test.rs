@@ -5,13 +5,15 @@
  */
 
 use nebula_rust::graph_client;
+use nebula_rust::graph_client::connection::Connection;
+use nebula_rust::graph_client::query::{Go, Sentence, Source};
 use rand::Rng;
 
 #[tokio::main]
 async fn main() {
     let address = "root:root@49.52.27.117:9669/testGraph";
     
-    let conf_nebula = graph_client::pool_config::PoolConfig::new_conf(address);
+    let conf_nebula = graph_client::pool_config::PoolConfig::new_conf(address).unwrap();
 
     let conn_nebula = graph_client::connection::Connection::new_from_conf(&conf_nebula).await.unwrap();
 
@@ -20,7 +22,10 @@ async fn main() {
     let session_id = resp.session_id.unwrap();
 
     // let resp = conn_nebula.execute(session_id, "use TokenTransfer;go from \"0x0016eccecffc25b94050187017eb59fa05c029aa\" OVER tx YIELD properties(edge);").await.unwrap();
-    let resp = conn_nebula.execute(session_id, "use testGraph;go 3 to 3 steps from \"0xh12rvhmxo22cszk73krl02vr82k6frfn0klk6ron\" OVER tx YIELD properties(edge);").await.unwrap();
+    let mut go = Go::new(Source::vids(["0xh12rvhmxo22cszk73krl02vr82k6frfn0klk6ron"]));
+    go.steps_range(3, 3).over("tx").yield_("properties(edge)");
+    let query = Connection::use_space_statement("testGraph").unwrap() + go.statement().unwrap().as_str();
+    let resp = conn_nebula.execute(session_id, &query).await.unwrap();
     
     // let ans = resp.into_json_with_name(resp.parse_resp().unwrap(),"edge".to_string());
 
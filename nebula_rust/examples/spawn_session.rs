@@ -0,0 +1,29 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use nebula_rust::graph_client::connection_pool;
+
+#[tokio::main]
+async fn main() {
+    let address = "root:root@127.0.0.1:9669/testGraph";
+
+    let pool = connection_pool::ConnectionPool_nebula::new_pool(address).unwrap();
+
+    pool.create_new_connection().await;
+
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        let pool = pool.clone();
+        // the session is owned by the task, it's not borrowed from the pool
+        handles.push(tokio::spawn(async move {
+            let session = pool.get_session(true).await.unwrap();
+            session.show_spaces().await.unwrap();
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
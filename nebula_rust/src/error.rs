@@ -0,0 +1,153 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use common::convert::ConvertError;
+use common::types::ErrorCode;
+
+use crate::graph_client::nebula_url::UrlError;
+
+/// The error returned by the client
+/// 客户端返回的错误
+#[derive(Debug)]
+pub enum Error {
+    /// The connection to server failed
+    Io(std::io::Error),
+    /// Connecting, waiting for the response or polling timed out
+    Timeout(String),
+    /// The request or response can't be encoded or decoded
+    Protocol(String),
+    /// The server rejected the username and password
+    Authentication { code: ErrorCode, message: String },
+    /// No connection became available within `acquire_timeout`
+    PoolExhausted,
+    /// The server failed to execute the request
+    Server { code: ErrorCode, message: String },
+    /// The connection string is malformed
+    Url(UrlError),
+    /// The result set has no such column
+    ColumnNotFound(String),
+    /// The value in column can't be converted into the rust type
+    Convert { column: String, error: ConvertError },
+    /// The nGQL statement can't be built from the arguments, e.g. a name with backtick
+    Statement(String),
+}
+
+/// `Result` with the client error
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Construct the server error from the `error_code` and `error_msg` of response
+    pub fn server(code: ErrorCode, error_msg: Option<&[u8]>) -> Self {
+        Error::Server {
+            code,
+            message: decode_message(error_msg),
+        }
+    }
+
+    /// Construct the authentication error from the `error_code` and `error_msg` of response
+    pub fn authentication(code: ErrorCode, error_msg: Option<&[u8]>) -> Self {
+        Error::Authentication {
+            code,
+            message: decode_message(error_msg),
+        }
+    }
+
+    /// The error code from server, if any
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::Authentication { code, .. } | Error::Server { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Whether the connection failed and the request may not be sent
+    pub fn is_connection_error(&self) -> bool {
+        matches!(self, Error::Io(_) | Error::Timeout(_))
+    }
+
+    /// Whether the tag or edge isn't found, e.g. graphd or storaged hasn't loaded the new schema yet.
+    /// Other semantic errors like a misspelled property are not
+    pub fn is_schema_missing(&self) -> bool {
+        match self {
+            Error::Server { code, message } => match *code {
+                ErrorCode::E_TAG_NOT_FOUND | ErrorCode::E_EDGE_NOT_FOUND => true,
+                // graphd validates the statement by its cached schema, e.g. "No schema found for `t'"
+                ErrorCode::E_SEMANTIC_ERROR => message.starts_with("No schema found"),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+fn decode_message(error_msg: Option<&[u8]>) -> String {
+    error_msg
+        .map(|msg| String::from_utf8_lossy(msg).to_string())
+        .unwrap_or_default()
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "connection error: {}", e),
+            Error::Timeout(what) => write!(f, "timed out: {}", what),
+            Error::Protocol(what) => write!(f, "protocol error: {}", what),
+            Error::Authentication { code, message } => {
+                write!(f, "authentication failed: {} {}", code, message)
+            }
+            Error::PoolExhausted => write!(f, "no connection available in pool"),
+            Error::Server { code, message } => write!(f, "server error: {} {}", code, message),
+            Error::Url(e) => write!(f, "invalid url: {}", e),
+            Error::ColumnNotFound(column) => write!(f, "column `{}` not found", column),
+            Error::Convert { column, error } => write!(f, "column `{}`: {}", column, error),
+            Error::Statement(what) => write!(f, "invalid statement: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Url(e) => Some(e),
+            Error::Convert { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::TimedOut {
+            Error::Timeout(e.to_string())
+        } else {
+            Error::Io(e)
+        }
+    }
+}
+
+impl From<UrlError> for Error {
+    fn from(e: UrlError) -> Self {
+        Error::Url(e)
+    }
+}
+
+impl From<fbthrift::NonthrowingFunctionError> for Error {
+    fn from(e: fbthrift::NonthrowingFunctionError) -> Self {
+        match e {
+            fbthrift::NonthrowingFunctionError::ApplicationException(ae) => {
+                Error::Protocol(format!("{:?}", ae))
+            }
+            fbthrift::NonthrowingFunctionError::ThriftError(e) => {
+                // The transport failure is wrapped as the io error
+                match e.chain().find_map(|cause| cause.downcast_ref::<std::io::Error>()) {
+                    Some(io) => std::io::Error::new(io.kind(), io.to_string()).into(),
+                    None => Error::Protocol(e.to_string()),
+                }
+            }
+        }
+    }
+}
@@ -4,20 +4,27 @@ use std::collections::HashMap;
  * This source code is licensed under Apache 2.0 License,
  * attached with Common Clause Condition 1.0, found in the LICENSES directory.
  */
-use std::io::Result;
 
+use common::types::Value;
 use fbthrift::BinaryProtocol;
 use fbthrift_transport::{tokio_io::transport::AsyncTransport, AsyncTransportConfiguration};
 use graph::client;
 use graph::client::GraphService;
 use tokio::net::TcpStream;
 
+use crate::error::{Error, Result};
+use crate::graph_client::escape;
 use crate::graph_client::transport_response_handler;
 use crate::graph_client::pool_config::PoolConfig;
+use crate::graph_client::nebula_schema;
 use crate::graph_client::nebula_schema::Tag;
+use crate::graph_client::nebula_schema::Ttl;
 use crate::graph_client::nebula_schema::ColType;
 use crate::graph_client::nebula_schema::InsertTagQuery;
 use crate::graph_client::nebula_schema::InsertEdgeQueryWithRank;
+use crate::graph_client::nebula_schema::{BatchLimit, BatchResult, StatementBatch};
+use crate::graph_client::nebula_schema::{DeleteEdgeQuery, DeleteTagQuery, DeleteVertexQuery, UpdateQuery};
+use crate::value::result_set::ResultSet;
 
 /// The simple abstraction of a connection to nebula graph server
 #[derive(Default)]
@@ -31,24 +38,60 @@ pub struct Connection {
             AsyncTransport<TcpStream, transport_response_handler::GraphTransportResponseHandler>,
         >,
     >,
+    // The [host:port] address of the server
+    address: String,
 }
 
 impl Connection {
     /// Create connection with the specified [host:port] address
     /// 使用指定的 [host:port] 地址创建连接
     pub async fn new_from_address(address: &str) -> Result<Connection> {
-        let stream = TcpStream::connect(address).await?;
-        let transport = AsyncTransport::new(
-            stream,
-            AsyncTransportConfiguration::new(
-                transport_response_handler::GraphTransportResponseHandler,
-            ),
-        );
+        Connection::new_with_timeout(address, 0, 0).await
+    }
+
+    /// Create connection with the specified [host:port] address,
+    /// `connect_timeout` bounds establishing the TCP connection and
+    /// `request_timeout` bounds waiting for each response, both in ms and 0 means the default
+    /// 使用指定地址创建连接，`connect_timeout` 限制建立 TCP 连接的时间，
+    /// `request_timeout` 限制等待每个响应的时间，单位为毫秒，0 表示默认值
+    pub async fn new_with_timeout(
+        address: &str,
+        connect_timeout: u32,
+        request_timeout: u32,
+    ) -> Result<Connection> {
+        let stream = if connect_timeout == 0 {
+            TcpStream::connect(address).await?
+        } else {
+            let timeout = std::time::Duration::from_millis(connect_timeout as u64);
+            match tokio::time::timeout(timeout, TcpStream::connect(address)).await {
+                Ok(stream) => stream?,
+                Err(_) => return Err(Error::Timeout(format!("connect to {}", address))),
+            }
+        };
+        let mut configuration =
+            AsyncTransportConfiguration::new(transport_response_handler::GraphTransportResponseHandler);
+        if request_timeout != 0 {
+            configuration.set_read_timeout(request_timeout);
+        }
+        let transport = AsyncTransport::new(stream, configuration);
         Ok(Connection {
             client: Some(client::GraphServiceImpl::new(transport)),
+            address: address.to_string(),
         })
     }
 
+    /// Get the [host:port] address of the server connected to
+    #[inline]
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Whether it's the null connection left in session after giving back
+    #[inline]
+    pub(crate) fn is_null(&self) -> bool {
+        self.client.is_none()
+    }
+
     /// Create connection with the specified [host:port]
     pub async fn new(host: &str, port: i32) -> Result<Connection> {
         let address = format!("{}:{}", host, port);
@@ -58,7 +101,7 @@ impl Connection {
     /// Create connection from nebula configuration
     pub async fn new_from_conf(conf: &PoolConfig) -> Result<Connection> {
         let address = conf.addresses[0].clone();
-        Connection::new_from_address(&address).await
+        Connection::new_with_timeout(&address, conf.timeout, conf.request_timeout).await
     }
 
     /// Authenticate by username and password
@@ -71,7 +114,7 @@ impl Connection {
         &self,
         username: &str,
         password: &str,
-    ) -> std::result::Result<graph::types::AuthResponse, common::types::ErrorCode> {
+    ) -> Result<graph::types::AuthResponse> {
         let result = self
             .client
             .as_ref()
@@ -80,11 +123,8 @@ impl Connection {
                 &username.to_string().into_bytes(),
                 &password.to_string().into_bytes(),
             )
-            .await;
-        if let Err(_) = result {
-            return Err(common::types::ErrorCode::E_RPC_FAILURE);
-        }
-        Ok(result.unwrap())
+            .await?;
+        Ok(result)
     }
 
     /// Sign out the authentication by session id which got by authenticating previous
@@ -92,11 +132,8 @@ impl Connection {
     pub async fn signout(
         &self,
         session_id: i64,
-    ) -> std::result::Result<(), common::types::ErrorCode> {
-        let result = self.client.as_ref().unwrap().signout(session_id).await;
-        if let Err(_) = result {
-            return Err(common::types::ErrorCode::E_RPC_FAILURE);
-        }
+    ) -> Result<()> {
+        self.client.as_ref().unwrap().signout(session_id).await?;
         Ok(())
     }
 
@@ -110,32 +147,54 @@ impl Connection {
         &self,
         session_id: i64,
         query: &str,
-    ) -> std::result::Result<graph::types::ExecutionResponse, common::types::ErrorCode> {
+    ) -> Result<graph::types::ExecutionResponse> {
         let result = self
             .client
             .as_ref()
             .unwrap()
             .execute(session_id, &query.to_string().into_bytes())
-            .await;
-        if let Err(_) = result {
-            return Err(common::types::ErrorCode::E_RPC_FAILURE);
+            .await?;
+        Ok(result)
+    }
+
+    /// Execute the query and check the `error_code` in response,
+    /// return the error with message from server if the query failed
+    /// 执行查询并检查响应中的 `error_code`，执行失败时返回带有服务端错误信息的错误
+    pub async fn query(&self, session_id: i64, query: &str) -> Result<ResultSet> {
+        ResultSet::from_response(self.execute(session_id, query).await?)
+    }
+
+    /// Check the authenticated connection by executing `YIELD 1`
+    /// 执行 `YIELD 1` 检查已认证的连接
+    pub async fn validate(&self, session_id: i64) -> bool {
+        match self.execute(session_id, "YIELD 1;").await {
+            Ok(resp) => resp.error_code == common::types::ErrorCode::SUCCEEDED,
+            Err(_) => false,
         }
-        Ok(result.unwrap())
     }
 
+    /// Check the connection by a lightweight round trip without authenticating,
+    /// the server answers the request of an unknown session with an error,
+    /// so any response means the connection works.
+    /// Return false only if the request failed, e.g. the socket is closed
+    /// 不认证，以未知会话发送一次请求来探测连接，服务端会返回错误响应，
+    /// 因此收到任何响应都说明连接可用，只有请求失败时返回 false
+    pub async fn ping(&self) -> bool {
+        self.execute(0, "YIELD 1;").await.is_ok()
+    }
 
+    /// List the spaces, the names are in the result set
+    /// 列出所有图空间，名称在结果集中
     #[inline]
-    pub async fn show_spaces(&self, session_id: i64){
-        let query = "show spaces;";
-        let resp = self.execute(session_id, query).await.unwrap();
-        resp.show_data();
+    pub async fn show_spaces(&self, session_id: i64) -> Result<ResultSet> {
+        self.query(session_id, "show spaces;").await
     }
 
     #[inline]
-    pub fn get_create_space_query(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str) -> String{
-        let mut query = String::from("CREATE SPACE IF NOT EXISTS `");
-        query += space_name;
-        query += "` (partition_num = ";
+    pub fn get_create_space_query(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str) -> Result<String>{
+        let mut query = String::from("CREATE SPACE IF NOT EXISTS ");
+        query += escape::quote_identifier(space_name)?.as_str();
+        query += " (partition_num = ";
         query += partition_num.to_string().as_str();
         query += ", replica_factor = ";
         query += replica_factor.to_string().as_str();
@@ -148,71 +207,63 @@ impl Connection {
             query += "INT64)";
         }
         if comment!=""{
-            query += " COMMENT = \"";
-            query += comment;
-            query += "\"";
+            query += " COMMENT = ";
+            query += escape::quote_string(comment).as_str();
         }
         query += ";";
-        query
+        Ok(query)
     }
     #[inline]
     // CREATE SPACE `testGraph` (partition_num = 15, replica_factor = 1, vid_type = FIXED_STRING(50)) COMMENT = "this is a graph for test"
-    pub async fn create_space(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str, session_id: i64){
-        let query = self.get_create_space_query(space_name, partition_num, replica_factor, is_fixed_string, fixed_string_len, comment);
+    pub async fn create_space(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str, session_id: i64) -> Result<ResultSet> {
+        let query = self.get_create_space_query(space_name, partition_num, replica_factor, is_fixed_string, fixed_string_len, comment)?;
         // println!("{}", query);
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query(session_id, query.as_str()).await
     }
 
     #[inline]
-    pub fn get_create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>) -> String{
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; CREATE ";
-        query += col_type.to_string().as_str();
-        query += " IF NOT EXISTS `";
-        query += tag_name;
-        query += "` (";
-        for i in 0..tags.len() {
-            query += tags[i].to_string().as_str();
-            if i!=tags.len()-1{
-                query += ",";
-            }
-        }
-        query += ")";
-        if comment!="".to_string(){
-            query += " COMMENT = \"";
-            query += comment;
-            query += "\"";
-        }
-        query += ";";
-        query
+    pub fn get_create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>) -> Result<String>{
+        Self::create_tag_or_edge_query(space_name, col_type, tag_name, comment, &tags, None)
     }
 
+    /// The statement of `create_tag_or_edge_with_ttl`, it switches to the space first
+    /// 创建带有存活时间的标签或边类型的语句，语句先切换到图空间
     #[inline]
-    pub async fn create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, session_id: i64){
+    pub fn get_create_tag_or_edge_with_ttl(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, ttl: &Ttl) -> Result<String>{
+        Self::create_tag_or_edge_query(space_name, col_type, tag_name, comment, &tags, Some(ttl))
+    }
+
+    /// `USE` the space, then `CREATE TAG/EDGE`
+    pub(crate) fn create_tag_or_edge_query(space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: &[Tag], ttl: Option<&Ttl>) -> Result<String> {
+        let mut query = Self::use_space_statement(space_name)?;
+        query += nebula_schema::create_schema_statement(col_type, tag_name, comment, tags, ttl)?.as_str();
+        Ok(query)
+    }
 
-        let query = self.get_create_tag_or_edge(space_name, col_type, tag_name, comment, tags);
+    #[inline]
+    pub async fn create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, session_id: i64) -> Result<ResultSet> {
+
+        let query = self.get_create_tag_or_edge(space_name, col_type, tag_name, comment, tags)?;
         //println!("{}", query);
 
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
+        self.query(session_id, query.as_str()).await
+    }
 
-        //println!("{:?}", _resp);
+    /// Create the tag or edge whose vertices or edges expire by the `ttl`
+    /// 创建带有存活时间的标签或边类型
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_tag_or_edge_with_ttl(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, ttl: &Ttl, session_id: i64) -> Result<ResultSet> {
+        let query = self.get_create_tag_or_edge_with_ttl(space_name, col_type, tag_name, comment, tags, ttl)?;
+        self.query(session_id, query.as_str()).await
     }
 
     #[inline]
     // INSERT VERTEX t2 (name, age) VALUES "11":("n1", 12);
-    pub async fn insert_tag(&self, space_name: &str, tag_name: &str, kv: HashMap<String, String>, vid: &str, session_id: i64){
-
-        if self.find_tag_or_edge(space_name, tag_name, ColType::Tag, session_id).await == false{
-            std::thread::sleep(std::time::Duration::from_millis(5000));
-        }
+    pub async fn insert_tag(&self, space_name: &str, tag_name: &str, kv: HashMap<String, Value>, vid: &str, session_id: i64) -> Result<ResultSet> {
 
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
+        let mut query = Self::use_space_statement(space_name)?;
         query += "INSERT VERTEX IF NOT EXISTS ";
-        query += tag_name;
+        query += escape::quote_identifier(tag_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -221,50 +272,117 @@ impl Connection {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += vid;
-        query += "\":";
+        query += " VALUES ";
+        query += escape::quote_string(vid).as_str();
+        query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query(session_id, &query).await
     }
 
     #[inline]
-    pub async fn insert_tags(&self, insert_tag_queries: Vec<InsertTagQuery>, session_id: i64){
+    pub async fn insert_tags(&self, insert_tag_queries: Vec<InsertTagQuery>, session_id: i64) -> Result<Vec<ResultSet>> {
+        let mut results = Vec::with_capacity(insert_tag_queries.len());
         for query in insert_tag_queries{
-            self.insert_tag(query.space_name.as_str(), query.tag_name.as_str(), query.kv, query.vid.as_str(), session_id).await;
+            results.push(self.insert_tag(query.space_name.as_str(), query.tag_name.as_str(), query.kv, query.vid.as_str(), session_id).await?);
         }
+        Ok(results)
     }
 
     #[inline]
-    pub async fn insert_edges(&self, insert_edge_queries: Vec<InsertEdgeQueryWithRank>, session_id: i64){
+    pub async fn insert_edges(&self, insert_edge_queries: Vec<InsertEdgeQueryWithRank>, session_id: i64) -> Result<Vec<ResultSet>> {
+        let mut results = Vec::with_capacity(insert_edge_queries.len());
         for query in insert_edge_queries{
-            self.insert_edge_with_rank(query.space_name.as_str(), query.edge_name.as_str(), query.kv, query.from_vertex.as_str(), query.to_vertex.as_str(), query.rank, session_id).await;
+            results.push(self.insert_edge_with_rank(query.space_name.as_str(), query.edge_name.as_str(), query.kv, query.from_vertex.as_str(), query.to_vertex.as_str(), query.rank, session_id).await?);
         }
+        Ok(results)
     }
 
-    #[inline]
-    // INSERT EDGE e2 (name, age) VALUES "11"->"13":("n1", 12);
-    pub async fn insert_edge(&self, space_name: &str, edge_name: &str, kv: HashMap<String, String>, from_vertex: &str, to_vertex: &str, session_id: i64){
+    /// Insert the vertices in batches, see `nebula_schema::batch_insert_tags`.
+    /// Unlike `insert_tags`, it doesn't check the tags exist, and all batches are sent even if some fail
+    /// 分批插入点，与 `insert_tags` 不同，不检查标签是否存在，部分批次失败时仍发送所有批次
+    pub async fn insert_tags_batched(&self, insert_tag_queries: &[InsertTagQuery], if_not_exists: bool, limit: &BatchLimit, session_id: i64) -> Result<Vec<BatchResult>> {
+        let limit = Self::limit_with_use(limit, insert_tag_queries.iter().map(|query| query.space_name.as_str()))?;
+        Ok(self.send_batches(nebula_schema::batch_insert_tags(insert_tag_queries, if_not_exists, &limit)?, session_id).await)
+    }
 
-        if self.find_tag_or_edge(space_name, edge_name, ColType::Edge, session_id).await == false{
-            std::thread::sleep(std::time::Duration::from_millis(5000));
+    /// Insert the edges in batches, see `nebula_schema::batch_insert_edges`.
+    /// Unlike `insert_edges`, it doesn't check the edges exist, and all batches are sent even if some fail
+    /// 分批插入边，与 `insert_edges` 不同，不检查边类型是否存在，部分批次失败时仍发送所有批次
+    pub async fn insert_edges_batched(&self, insert_edge_queries: &[InsertEdgeQueryWithRank], if_not_exists: bool, limit: &BatchLimit, session_id: i64) -> Result<Vec<BatchResult>> {
+        let limit = Self::limit_with_use(limit, insert_edge_queries.iter().map(|query| query.space_name.as_str()))?;
+        Ok(self.send_batches(nebula_schema::batch_insert_edges(insert_edge_queries, if_not_exists, &limit)?, session_id).await)
+    }
+
+    /// The limit left for the statement after the `USE` prefix of `send_batches`
+    fn limit_with_use<'a, I: IntoIterator<Item = &'a str>>(limit: &BatchLimit, spaces: I) -> Result<BatchLimit> {
+        let mut prefix = 0;
+        for space_name in spaces {
+            prefix = prefix.max(Self::use_space_statement(space_name)?.len());
+        }
+        let mut limit = limit.clone();
+        if limit.max_bytes != 0 {
+            // 0 is no limit, keep at least 1 so every row is sent alone
+            limit.max_bytes = limit.max_bytes.saturating_sub(prefix).max(1);
         }
+        Ok(limit)
+    }
+
+    async fn send_batches(&self, batches: Vec<StatementBatch>, session_id: i64) -> Vec<BatchResult> {
+        let mut results = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let result = match Self::use_space_statement(&batch.space_name) {
+                Ok(query) => self.query(session_id, &(query + batch.statement.as_str())).await,
+                Err(e) => Err(e),
+            };
+            results.push(BatchResult { batch, result });
+        }
+        results
+    }
+
+    /// Update or upsert the vertex or edge, the yielded properties are in the result set
+    /// 更新或插入更新点、边，`YIELD` 的属性在结果集中
+    pub async fn update(&self, query: &UpdateQuery, session_id: i64) -> Result<ResultSet> {
+        let statement = Self::use_space_statement(&query.space_name)? + query.statement()?.as_str();
+        self.query(session_id, &statement).await
+    }
+
+    /// Delete the vertices in batches, all batches are sent even if some fail
+    /// 分批删除点，部分批次失败时仍发送所有批次
+    pub async fn delete_vertices(&self, query: &DeleteVertexQuery, limit: &BatchLimit, session_id: i64) -> Result<Vec<BatchResult>> {
+        let limit = Self::limit_with_use(limit, std::iter::once(query.space_name.as_str()))?;
+        Ok(self.send_batches(query.batches(&limit)?, session_id).await)
+    }
+
+    /// Delete the edges in batches, all batches are sent even if some fail
+    /// 分批删除边，部分批次失败时仍发送所有批次
+    pub async fn delete_edges(&self, query: &DeleteEdgeQuery, limit: &BatchLimit, session_id: i64) -> Result<Vec<BatchResult>> {
+        let limit = Self::limit_with_use(limit, std::iter::once(query.space_name.as_str()))?;
+        Ok(self.send_batches(query.batches(&limit)?, session_id).await)
+    }
+
+    /// Delete the tags from vertices in batches, all batches are sent even if some fail
+    /// 分批删除点上的标签，部分批次失败时仍发送所有批次
+    pub async fn delete_tags(&self, query: &DeleteTagQuery, limit: &BatchLimit, session_id: i64) -> Result<Vec<BatchResult>> {
+        let limit = Self::limit_with_use(limit, std::iter::once(query.space_name.as_str()))?;
+        Ok(self.send_batches(query.batches(&limit)?, session_id).await)
+    }
+
+    #[inline]
+    // INSERT EDGE e2 (name, age) VALUES "11"->"13":("n1", 12);
+    pub async fn insert_edge(&self, space_name: &str, edge_name: &str, kv: HashMap<String, Value>, from_vertex: &str, to_vertex: &str, session_id: i64) -> Result<ResultSet> {
 
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
+        let mut query = Self::use_space_statement(space_name)?;
         query += "INSERT EDGE IF NOT EXISTS ";
-        query += edge_name;
+        query += escape::quote_identifier(edge_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -273,33 +391,31 @@ impl Connection {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += from_vertex;
-        query += "\" -> \"";
-        query += to_vertex;
-        query += "\":";
+        query += " VALUES ";
+        query += escape::quote_string(from_vertex).as_str();
+        query += " -> ";
+        query += escape::quote_string(to_vertex).as_str();
+        query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
+        self.query(session_id, &query).await
     }
 
 
     #[inline]
     // INSERT EDGE e2 (name, age) VALUES "11"->"13"@1:("n1", 12);
-    pub async fn insert_edge_with_rank(&self, space_name: &str, edge_name: &str, kv: HashMap<String, String>, from_vertex: &str, to_vertex: &str, rank: i64, session_id: i64){
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
+    pub async fn insert_edge_with_rank(&self, space_name: &str, edge_name: &str, kv: HashMap<String, Value>, from_vertex: &str, to_vertex: &str, rank: i64, session_id: i64) -> Result<ResultSet> {
+        let mut query = Self::use_space_statement(space_name)?;
         query += "INSERT EDGE IF NOT EXISTS ";
-        query += edge_name;
+        query += escape::quote_identifier(edge_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -308,49 +424,44 @@ impl Connection {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += from_vertex;
-        query += "\" -> \"";
-        query += to_vertex;
-        query += "\"@";
+        query += " VALUES ";
+        query += escape::quote_string(from_vertex).as_str();
+        query += " -> ";
+        query += escape::quote_string(to_vertex).as_str();
+        query += "@";
         query += rank.to_string().as_str();
         query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query(session_id, &query).await
     }
 
     #[inline]
     // CREATE TAG INDEX `index_tag` on `stu`      (`name`(10), `age`) COMMENT "this is an index for tag"
-    pub async fn create_index(&self, space_name: &str, index_type: ColType, tag_or_edge_name: &str, index_name: &str, comment: &str, indexed_properties: HashMap<String, u8>, session_id: i64){
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
+    pub async fn create_index(&self, space_name: &str, index_type: ColType, tag_or_edge_name: &str, index_name: &str, comment: &str, indexed_properties: HashMap<String, u8>, session_id: i64) -> Result<ResultSet> {
+        let mut query = Self::use_space_statement(space_name)?;
         query += "CREATE ";
         query += index_type.to_string().as_str();
-        query += " INDEX `";
-        query += index_name;
-        query += "` on `";
-        query += tag_or_edge_name;
-        query += "`(";
+        query += " INDEX ";
+        query += escape::quote_identifier(index_name)?.as_str();
+        query += " on ";
+        query += escape::quote_identifier(tag_or_edge_name)?.as_str();
+        query += "(";
 
         let mut properties = String::from("");
         for (k,v) in indexed_properties {
             if properties.len()!=0{
                 properties += ",";
             }
-            let mut property = String::from("`");
-            property += k.as_str();
-            property += "`";
+            let mut property = escape::quote_identifier(k.as_str())?;
             if v==0 {
             }else{
                 property += "(";
@@ -363,40 +474,41 @@ impl Connection {
         query += properties.as_str();
         query += ") ";
         if comment!=""{
-            query += "COMMENT \"";
-            query += comment;
-            query += "\"";
+            query += "COMMENT ";
+            query += escape::quote_string(comment).as_str();
         }
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(session_id, query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query(session_id, query.as_str()).await
     }
 
     #[inline]
-    pub async fn find_tag_or_edge(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType, session_id: i64) -> bool{
-        let mut query = Self::use_space(space_name);
+    pub async fn find_tag_or_edge(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType, session_id: i64) -> Result<bool> {
+        let mut query = Self::use_space_statement(space_name)?;
         match col_type {
             ColType::Edge => query += "show edges;",
             ColType::Tag => query += "show tags;",
         }
-        let resp = self.execute(session_id, query.as_str()).await.unwrap();
-        // print!("{:?}", resp);
-        let res = resp.get_sVal();
-        match res {
-            Some(tags) => {
-                for tag in tags{
-                    if tag == tag_or_edge_name.to_string(){
-                        return true;
-                    }
-                }
-            }
-            None => return false
-        }
-        return false;
+        let resp = self.query(session_id, query.as_str()).await?;
+        Ok(resp
+            .response()
+            .get_sVal()
+            .is_some_and(|names| names.iter().any(|name| name == tag_or_edge_name)))
     }
 
+    /// Wait until graphd and storaged can use the tag or edge, `SHOW TAGS/EDGES` of `find_tag_or_edge`
+    /// is answered by metad before them. Fail with `Error::Timeout` if it's still missing after `timeout` ms
+    /// 等待 graphd 与 storaged 可以使用标签或边类型，超过 `timeout` 毫秒仍缺失时返回超时错误
+    pub async fn wait_for_schema(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType, timeout: u32, session_id: i64) -> Result<()> {
+        nebula_schema::wait_for_schema(space_name, tag_or_edge_name, col_type, timeout, |query| async move {
+            self.query(session_id, &(Self::use_space_statement(space_name)? + query.as_str())).await
+        })
+        .await
+    }
+
+    /// Get the `use` statement of the space, the name isn't quoted
+    #[deprecated(note = "the name isn't quoted, use `use_space_statement` instead")]
     #[inline]
     pub fn use_space(space_name: &str) -> String{
         let mut line = String::from("use ");
@@ -405,4 +517,61 @@ impl Connection {
         line
     }
 
+    /// The prefix `USE `space`; ` to run the following statement in the space
+    /// 在该图空间中执行后续语句的前缀 `USE `space`; `
+    #[inline]
+    pub fn use_space_statement(space_name: &str) -> Result<String>{
+        let mut line = String::from("USE ");
+        line += escape::quote_identifier(space_name)?.as_str();
+        line += "; ";
+        Ok(line)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_client::nebula_schema::DataType;
+
+    #[test]
+    fn test_create_tag_or_edge_query() {
+        let tags = [Tag::new("t", DataType::Int, false, "", "")];
+        assert_eq!(
+            Connection::create_tag_or_edge_query("s", ColType::Tag, "a", "", &tags, None).unwrap(),
+            "USE `s`; CREATE tag IF NOT EXISTS `a` (`t` int NOT NULL );"
+        );
+        let ttl = Ttl::new(60, "t");
+        assert_eq!(
+            Connection::create_tag_or_edge_query("s", ColType::Edge, "a", "", &tags, Some(&ttl)).unwrap(),
+            "USE `s`; CREATE edge IF NOT EXISTS `a` (`t` int NOT NULL ) TTL_DURATION = 60, TTL_COL = \"t\";"
+        );
+    }
+
+    #[test]
+    fn test_limit_with_use() {
+        // "USE `space`; " is 13 bytes
+        let limit = BatchLimit::new().max_bytes(100).clone();
+        let reduced = Connection::limit_with_use(&limit, vec!["s", "space"]).unwrap();
+        assert_eq!(reduced.max_bytes, 87);
+        assert_eq!(reduced.max_rows, limit.max_rows);
+
+        let small = BatchLimit::new().max_bytes(10).clone();
+        assert_eq!(Connection::limit_with_use(&small, vec!["space"]).unwrap().max_bytes, 1);
+        let unlimited = BatchLimit::new().max_bytes(0).clone();
+        assert_eq!(Connection::limit_with_use(&unlimited, vec!["space"]).unwrap().max_bytes, 0);
+        assert!(matches!(Connection::limit_with_use(&limit, vec!["a`b"]), Err(Error::Statement(_))));
+
+        let mut delete = DeleteVertexQuery::new("space", false);
+        delete.vid("a").vid("b").vid("c");
+        // "DELETE VERTEX \"a\", \"b\", \"c\";" is 28 bytes, 41 with the prefix
+        let limit = BatchLimit::new().max_bytes(40).clone();
+        assert_eq!(delete.batches(&limit).unwrap().len(), 1);
+        let reduced = Connection::limit_with_use(&limit, std::iter::once("space")).unwrap();
+        let batches = delete.batches(&reduced).unwrap();
+        assert_eq!(batches.len(), 2);
+        for batch in batches {
+            assert!(Connection::use_space_statement("space").unwrap().len() + batch.statement.len() <= 40);
+        }
+    }
 }
@@ -1,23 +1,91 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::stream::StreamExt;
+
+use crate::error::{Error, Result};
 use crate::graph_client::connection::Connection;
+use crate::graph_client::load_balancer::{HostInfo, LoadBalancer};
 use crate::graph_client::pool_config::PoolConfig;
 use crate::graph_client::session::Session;
 
+/// The connection waiting in pool and the time it became idle
+/// 池中的空闲连接及其开始空闲的时间
+struct IdleConnection {
+    conn: Connection,
+    since: std::time::Instant,
+}
+
+/// Health of one graph server address
+/// 单个图服务地址的健康状态
+struct HostState {
+    address: String,
+    weight: u32,
+    /// Count of sessions holding connections to this host
+    sessions: usize,
+    /// Consecutive failures to connect
+    failures: u32,
+    /// Skipped in rotation until then
+    down_until: Option<std::time::Instant>,
+}
+
+impl HostState {
+    fn new(address: String, weight: u32) -> Self {
+        HostState {
+            address,
+            weight,
+            sessions: 0,
+            failures: 0,
+            down_until: None,
+        }
+    }
+
+    fn is_up(&self, now: std::time::Instant) -> bool {
+        match self.down_until {
+            Some(down_until) => now >= down_until,
+            None => true,
+        }
+    }
+}
+
+impl IdleConnection {
+    fn new(conn: Connection) -> Self {
+        IdleConnection {
+            conn,
+            since: std::time::Instant::now(),
+        }
+    }
+}
+
 /// The pool of connection to server, it's MT-safe to access.
 /// 与服务器的连接池，它是 MT 安全访问的。
 pub struct ConnectionPool_nebula {
-    /// The connections
-    /// The interior mutable to enable could get multiple sessions in one scope
-    /// 内部可变启用可以在一个范围内获得多个会话
-    conns: std::sync::Mutex<std::cell::RefCell<std::collections::LinkedList<Connection>>>,
+    /// The idle connections
+    /// 空闲连接
+    conns: std::sync::Mutex<std::collections::VecDeque<IdleConnection>>,
+    /// Each session holds one permit, waiters are served in FIFO order
+    /// when the pool is exhausted
+    /// 每个会话持有一个许可，连接池耗尽时按先进先出顺序等待
+    permits: Arc<tokio::sync::Semaphore>,
     /// It should be immutable
     /// 它应该是不可变的
     config: PoolConfig,
-    /// Address cursor
-    /// 地址光标
-    cursor: std::sync::Mutex<usize>,
-    /// The total count of connections, contains which hold by session
-    /// 连接总数，包含会话持有的连接数
-    conns_count: std::sync::Mutex<usize>,
+    /// Choose the address for new session
+    /// 为新会话选择地址
+    balancer: Arc<dyn LoadBalancer>,
+    /// Health of each address in `config.addresses`, failed hosts are skipped for a backoff period
+    /// 每个地址的健康状态，失败的地址在退避期内被跳过
+    hosts: std::sync::Mutex<Vec<HostState>>,
+    /// The total count of connections, contains which hold by session and which being connected,
+    /// a slot is reserved before connecting so the limit is never exceeded
+    /// 连接总数，包含会话持有的连接数和正在建立的连接数，建立连接前先预留名额，保证不超过上限
+    conns_count: AtomicUsize,
+    /// The background task evicting idle connections and keeping the min size
+    /// 驱逐空闲连接并维持最小连接数的后台任务
+    maintenance: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// When the idle connections were probed last time
+    /// 上次探测空闲连接的时间
+    last_health_check: std::sync::Mutex<std::time::Instant>,
 }
 
 impl ConnectionPool_nebula {
@@ -26,36 +94,46 @@ impl ConnectionPool_nebula {
 
         // println!("conf: {:?}", conf.clone());
 
-        let conns = std::collections::LinkedList::<Connection>::new();
+        let conns = std::collections::VecDeque::<IdleConnection>::new();
         let pool = ConnectionPool_nebula {
-            conns: std::sync::Mutex::new(std::cell::RefCell::new(conns)),
+            conns: std::sync::Mutex::new(conns),
+            permits: Arc::new(tokio::sync::Semaphore::new(
+                conf.max_connection_pool_size as usize,
+            )),
             config: conf.clone(),
-            cursor: std::sync::Mutex::new(0),
-            conns_count: std::sync::Mutex::new(0),
+            balancer: conf.load_balancer.build(),
+            hosts: std::sync::Mutex::new(
+                conf.addresses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, address)| {
+                        HostState::new(address.clone(), conf.weights.get(i).cloned().unwrap_or(1))
+                    })
+                    .collect(),
+            ),
+            conns_count: AtomicUsize::new(0),
+            maintenance: std::sync::Mutex::new(None),
+            last_health_check: std::sync::Mutex::new(std::time::Instant::now()),
         };
         assert!(pool.config.min_connection_pool_size <= pool.config.max_connection_pool_size);
         // pool.new_connection(pool.config.min_connection_pool_size).await;
         pool
     }
 
+    /// Construct pool by the configuration and wrap it in `Arc`,
+    /// sessions hold a handle to the pool so they could outlive the scope which got them
+    /// 构造连接池并包装为 `Arc`，会话持有连接池句柄，因此可以移动到其他任务中
+    pub fn new_shared(conf: &PoolConfig) -> Arc<Self> {
+        Arc::new(ConnectionPool_nebula::new(conf))
+    }
+
+    /// Construct pool by connection string, see `PoolConfig::new_conf`
     // 创建一个新的connection，但是不在这个方法里连接nebula
-    pub fn new_pool(nebula_url: &str) -> Self{
-        let v:Vec<&str> = nebula_url.split('@').collect();
-        let v2:Vec<&str> = v[1].split('/').collect();
-        let add = String::from(v2[0]);
-        let v3:Vec<&str> = v[0].split(':').collect();
-        let username = String::from(v3[0]);
-        let password = String::from(v3[1]);
-
-        let mut conf = PoolConfig::new();
-        conf.min_connection_pool_size(2)
-            .max_connection_pool_size(10)
-            .address(add)
-            .set_username(username)
-            .set_password(password);
-        let pool = ConnectionPool_nebula::new(&conf);
+    pub fn new_pool(nebula_url: &str) -> Result<Arc<Self>> {
+        let conf = PoolConfig::new_conf(nebula_url)?;
+        let pool = ConnectionPool_nebula::new_shared(&conf);
         // pool.create_new_connection().await;
-        pool
+        Ok(pool)
     }
 
 
@@ -65,146 +143,509 @@ impl ConnectionPool_nebula {
 
     /// Get a session authenticated by username and password
     /// retry_connect means keep the connection available if true
+    /// The session holds an `Arc` of the pool, so it's `Send + 'static`
+    /// Wait for a connection given back when the pool is exhausted,
+    /// `Error::PoolExhausted` is returned if waiting longer than `acquire_timeout`
+    /// Broken idle connections are discarded and the next one is tried
     /// 获取由用户名和密码验证的会话 retry_connect 表示如果为真则保持连接可用
+    /// 连接池耗尽时等待归还的连接，超过 `acquire_timeout` 返回 `Error::PoolExhausted`
+    /// 损坏的空闲连接会被丢弃并尝试下一个
     pub async fn get_session(
-        &self,
+        self: &Arc<Self>,
         // username: &str,
         // password: &str,
         retry_connect: bool,
-    ) -> std::result::Result<Session<'_>, common::types::ErrorCode> {
+    ) -> Result<Session> {
 
         let username = self.config.username.clone();
         let password = self.config.password.clone();
 
 
         // println!("==========getSession=============");
-        if self.conns.lock().unwrap().borrow_mut().is_empty() {
-            self.new_connection(1).await;
+        let permit = self.acquire_permit().await?;
+        let (conn, resp) = self
+            .open_session(&username, &password, &self.config.space)
+            .await?;
+        self.session_opened(&conn);
+        Ok(Session::new(
+            resp.session_id.unwrap(),
+            conn,
+            Arc::clone(self),
+            username.to_string(),
+            password.to_string(),
+            if let Some(time_zone_name) = resp.time_zone_name {
+                std::str::from_utf8(&time_zone_name).unwrap().to_string()
+            } else {
+                String::new()
+            },
+            resp.time_zone_offset_seconds.unwrap(),
+            retry_connect,
+            permit,
+        ))
+    }
+
+    /// Replace the connection of a session whose server session expired or whose connection
+    /// broke, the old one is given back if `usable`. The session keeps its permit
+    /// 替换服务端会话过期或连接断开的会话的连接，`usable` 时归还旧连接，会话保留其许可
+    pub(crate) async fn reconnect(
+        &self,
+        old: Connection,
+        usable: bool,
+        username: &str,
+        password: &str,
+        space: &str,
+    ) -> Result<(Connection, i64)> {
+        if !old.is_null() {
+            self.release_connection(old, usable);
         }
-        let conn = self.conns.lock().unwrap().borrow_mut().pop_back();
-        if let Some(conn) = conn {
+        let (conn, resp) = self.open_session(username, password, space).await?;
+        self.session_opened(&conn);
+        Ok((conn, resp.session_id.unwrap()))
+    }
+
+    /// Get a connection for the caller which holds a permit, authenticate and switch to
+    /// the space if not empty. The broken idle connections and the ones failing validation
+    /// are retried at most once for each idle connection plus one fresh connection
+    /// 为持有许可的调用者获取连接，进行身份验证，图空间不为空时切换到该图空间，
+    /// 损坏或校验失败的连接最多重试空闲连接数加一次
+    async fn open_session(
+        &self,
+        username: &str,
+        password: &str,
+        space: &str,
+    ) -> Result<(Connection, graph::types::AuthResponse)> {
+        // Each idle connection and one fresh connection at most,
+        // so a server which keeps failing never makes it loop forever
+        let max_attempts = self.len() + 1;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let (conn, from_idle) = self.acquire_connection().await?;
             // get authentication with username and password
-            let resp = conn.authenticate(username.as_str(), password.as_str()).await?;
+            let resp = match conn.authenticate(username, password).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    self.discard(conn);
+                    if from_idle && attempt < max_attempts {
+                        // maybe a half-closed socket, try the next one
+                        continue;
+                    }
+                    return Err(e);
+                }
+            };
             if resp.error_code != common::types::ErrorCode::SUCCEEDED {
-                return Err(resp.error_code);
+                self.give_back(conn);
+                return Err(Error::authentication(resp.error_code, resp.error_msg.as_deref()));
             }
-            Ok(Session::new(
-                resp.session_id.unwrap(),
-                conn,
-                self,
-                username.to_string(),
-                password.to_string(),
-                if let Some(time_zone_name) = resp.time_zone_name {
-                    std::str::from_utf8(&time_zone_name).unwrap().to_string()
-                } else {
-                    String::new()
-                },
-                resp.time_zone_offset_seconds.unwrap(),
-                retry_connect,
-            ))
+            let session_id = resp.session_id.unwrap();
+            if self.config.test_on_borrow && !conn.validate(session_id).await {
+                let _ = conn.signout(session_id).await;
+                let address = conn.address().to_string();
+                self.discard(conn);
+                if attempt < max_attempts {
+                    continue;
+                }
+                return Err(Error::Protocol(format!(
+                    "connection to {} failed the validation by `YIELD 1`",
+                    address
+                )));
+            }
+            if !space.is_empty() {
+                let result = match Connection::use_space_statement(space) {
+                    Ok(query) => conn.query(session_id, &query).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    match conn.signout(session_id).await {
+                        Ok(_) => self.give_back(conn),
+                        Err(_) => self.discard(conn),
+                    }
+                    return Err(e);
+                }
+            }
+            return Ok((conn, resp));
+        }
+    }
+
+    /// Wait for the permit to hold a connection, bounded by `acquire_timeout`
+    /// 等待持有连接的许可，最长等待 `acquire_timeout`
+    async fn acquire_permit(&self) -> Result<tokio::sync::OwnedSemaphorePermit> {
+        let acquire = Arc::clone(&self.permits).acquire_owned();
+        let permit = if self.config.acquire_timeout == 0 {
+            acquire.await
         } else {
-            Err(common::types::ErrorCode::E_UNKNOWN)
+            let timeout = std::time::Duration::from_millis(self.config.acquire_timeout as u64);
+            match tokio::time::timeout(timeout, acquire).await {
+                Ok(permit) => permit,
+                Err(_) => return Err(Error::PoolExhausted),
+            }
+        };
+        // The semaphore is never closed
+        permit.map_err(|_| Error::PoolExhausted)
+    }
+
+    /// Get a connection to the host chosen by load balancer for the caller which holds a permit,
+    /// an idle one is preferred, otherwise connect to the host if the pool is not full.
+    /// Try each address at most once, also return whether it's an idle connection
+    /// 为持有许可的调用者获取负载均衡器所选地址的连接，优先使用空闲连接，连接池未满时新建连接
+    /// 每个地址最多尝试一次，同时返回是否为空闲连接
+    async fn acquire_connection(&self) -> Result<(Connection, bool)> {
+        let mut last_error = Error::PoolExhausted;
+        for _ in 0..self.config.addresses.len() {
+            let cursor = self.cursor();
+            if let Some(conn) = self.take_idle(Some(&self.config.addresses[cursor])) {
+                return Ok((conn, true));
+            }
+            if !self.reserve() {
+                break;
+            }
+            match self.connect_to(cursor).await {
+                Ok(conn) => return Ok((conn, false)),
+                Err(e) => {
+                    self.unreserve();
+                    last_error = e;
+                }
+            }
+        }
+        // The chosen hosts are unreachable or the pool is full, use any idle one
+        match self.take_idle(None) {
+            Some(conn) => Ok((conn, true)),
+            None => Err(last_error),
+        }
+    }
+
+    /// Take the most recently used idle connection, to the address if specified
+    /// 取出最近使用的空闲连接，指定地址时只取该地址的连接
+    fn take_idle(&self, address: Option<&str>) -> Option<Connection> {
+        let mut conns = self.conns.lock().unwrap();
+        let index = match address {
+            Some(address) => conns.iter().rposition(|idle| idle.conn.address() == address)?,
+            None => conns.len().checked_sub(1)?,
+        };
+        conns.remove(index).map(|idle| idle.conn)
+    }
+
+    /// Count the session on the host of its connection
+    /// 在连接所属地址上计数会话
+    fn session_opened(&self, conn: &Connection) {
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(host) = hosts.iter_mut().find(|host| host.address == conn.address()) {
+            host.sessions += 1;
+        }
+    }
+
+    fn session_closed(&self, conn: &Connection) {
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(host) = hosts.iter_mut().find(|host| host.address == conn.address()) {
+            host.sessions = host.sessions.saturating_sub(1);
+        }
+    }
+
+    /// Connect to the address at cursor and record the health of the host
+    /// 连接指定地址并记录该地址的健康状态
+    async fn connect_to(&self, cursor: usize) -> Result<Connection> {
+        let result = Connection::new_with_timeout(
+            &self.config.addresses[cursor],
+            self.config.timeout,
+            self.config.request_timeout,
+        )
+        .await;
+        match &result {
+            Ok(_) => self.mark_up(cursor),
+            Err(_) => self.mark_down(cursor),
+        }
+        result
+    }
+
+    fn mark_up(&self, cursor: usize) {
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts[cursor].failures = 0;
+        hosts[cursor].down_until = None;
+    }
+
+    /// Skip the host for a backoff period, which doubles on each consecutive failure
+    /// 在退避期内跳过该地址，连续失败时退避时间加倍
+    fn mark_down(&self, cursor: usize) {
+        let base = if self.config.host_backoff == 0 {
+            10_000
+        } else {
+            self.config.host_backoff as u64
+        };
+        let mut hosts = self.hosts.lock().unwrap();
+        let host = &mut hosts[cursor];
+        host.failures = host.failures.saturating_add(1);
+        let backoff = base << (host.failures - 1).min(5);
+        host.down_until =
+            Some(std::time::Instant::now() + std::time::Duration::from_millis(backoff));
+    }
+
+    /// The addresses which are currently considered up,
+    /// the failed ones are excluded until they are connected again
+    /// 当前被认为可用的地址，失败的地址在重新连接成功前不包含在内
+    pub fn healthy_addresses(&self) -> Vec<String> {
+        self.hosts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|host| host.failures == 0)
+            .map(|host| host.address.clone())
+            .collect()
+    }
+
+    /// Re-probe the failed hosts whose backoff period is over
+    /// 重新探测退避期已过的失败地址
+    async fn probe_hosts(&self) {
+        let now = std::time::Instant::now();
+        let due: Vec<usize> = self
+            .hosts
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, host)| host.failures != 0 && host.is_up(now))
+            .map(|(cursor, _)| cursor)
+            .collect();
+        for cursor in due {
+            let _ = self.connect_to(cursor).await;
         }
     }
 
     /// Get the count of connections
     #[inline]
     pub fn len(&self) -> usize {
-        self.conns.lock().unwrap().borrow().len()
+        self.conns.lock().unwrap().len()
     }
 
     /// Give back the connection to pool
     #[inline]
     pub fn give_back(&self, conn: Connection) {
-        self.conns.lock().unwrap().borrow_mut().push_back(conn);
+        self.conns.lock().unwrap().push_back(IdleConnection::new(conn));
     }
 
-    pub async fn new_connection(&self, inc: u32) {
-        assert!(inc != 0);
-        // TODO concurrent these
-        let mut count = 0;
-        let mut loop_count = 0;
-        let loop_limit = inc as usize * self.config.addresses.len();
-        while count < inc {
-
-            let conns_count_mutex: usize;
-
-            {
-                conns_count_mutex = * self.conns_count.lock().unwrap();
-            }
-
-            if count as usize
-                + conns_count_mutex
-                >= self.config.max_connection_pool_size as usize
-            {
-                // Reach the pool size limit
-                break;
-            }
-            let cursor = { self.cursor() };
-            match Connection::new_from_address(&self.config.addresses[cursor]).await {
-                Ok(conn) => {
-                    // append the conn to the conenction list
-                    self.conns.lock().unwrap().borrow_mut().push_back(conn);
-                    count += 1;
-                }
-                Err(_) => (),
-            };
-            loop_count += 1;
-            if loop_count > loop_limit {
-                // Can't get so many connections, avoid dead loop
-                break;
-            }
+    /// Give back the connection of a finished session if it's still usable, otherwise discard it
+    /// 会话结束时连接可用则归还，否则丢弃
+    pub(crate) fn release_connection(&self, conn: Connection, usable: bool) {
+        self.session_closed(&conn);
+        if usable {
+            self.give_back(conn);
+        } else {
+            self.discard(conn);
         }
-        // Release ordering make sure inc happened after creating new connections
-        // 发布订单确保在创建新连接后发生 inc
-        
-        let mut conns_count_add = self.conns_count.lock().unwrap();
-        * conns_count_add += count as usize;
+    }
 
-        // self.conns_count
-        //     .borrow_mut()
-        //     .fetch_add(count as usize, std::sync::atomic::Ordering::Release);
+    /// Drop the connection and forget it in the total count
+    /// 丢弃连接并从连接总数中减去
+    pub(crate) fn discard(&self, conn: Connection) {
+        drop(conn);
+        self.unreserve();
     }
 
+    /// Reserve a slot for a new connection, false if the pool is full
+    /// 为新连接预留名额，连接池已满时返回 false
+    fn reserve(&self) -> bool {
+        let max = self.config.max_connection_pool_size as usize;
+        self.conns_count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                if count < max {
+                    Some(count + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
 
-        // cursor on the server addresses
-    // 服务器地址上的光标
-    fn cursor(&self) -> usize {
-        // println!("current cursor: {:?}", self.cursor.borrow().load(std::sync::atomic::Ordering::Relaxed));
+    /// Release the slot of a discarded or failed connection
+    /// 释放被丢弃或建立失败的连接的名额
+    fn unreserve(&self) {
+        let _ = self
+            .conns_count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                count.checked_sub(1)
+            });
+    }
 
-        // println!("current config.addresses.len(): {:?}", self.config.addresses.len());
-        let cursor_mutex: usize ;
+    /// Start the background task which closes connections idle longer than `idle_time`
+    /// and refills the pool to `min_connection_pool_size`, it runs until `shutdown`.
+    /// Calling it again while the task is running does nothing
+    /// 启动后台维护任务：关闭空闲超过 `idle_time` 的连接，并补充到最小连接数，直到 `shutdown`
+    pub fn start_maintenance(self: &Arc<Self>) {
+        let mut maintenance = self.maintenance.lock().unwrap();
+        if maintenance.is_some() {
+            return;
+        }
+        let interval = if self.config.maintenance_interval == 0 {
+            std::time::Duration::from_secs(1)
+        } else {
+            std::time::Duration::from_millis(self.config.maintenance_interval as u64)
+        };
+        // Don't keep the pool alive by the task itself
+        let pool = Arc::downgrade(self);
+        *maintenance = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match pool.upgrade() {
+                    Some(pool) => pool.maintain().await,
+                    None => break,
+                }
+            }
+        }));
+    }
 
-        {
-            cursor_mutex = * self.cursor.lock().unwrap();
+    /// Stop the background maintenance and close all idle connections,
+    /// connections held by sessions are closed when the sessions are released
+    /// 停止后台维护并关闭所有空闲连接，会话持有的连接在会话释放时关闭
+    pub fn shutdown(&self) {
+        if let Some(handle) = self.maintenance.lock().unwrap().take() {
+            handle.abort();
         }
+        let idle = std::mem::take(&mut *self.conns.lock().unwrap());
+        for idle in idle {
+            self.discard(idle.conn);
+        }
+    }
 
-        if cursor_mutex  >= self.config.addresses.len()
-        {
-            {
-                let mut a = self.cursor.lock().unwrap();
-                * a = 0;
+    /// Probe the idle connections one by one and discard the broken ones,
+    /// the others stay available to sessions while probing
+    /// 逐个探测空闲连接并丢弃损坏的连接，探测期间其余连接仍可被会话使用
+    async fn check_idle_connections(&self) {
+        // The probed ones are pushed back, so each one is probed at most once in this round
+        let count = self.len();
+        for _ in 0..count {
+            let idle = match self.conns.lock().unwrap().pop_front() {
+                Some(idle) => idle,
+                None => break,
+            };
+            if idle.conn.ping().await {
+                self.conns.lock().unwrap().push_back(idle);
+            } else {
+                self.discard(idle.conn);
             }
-                // println!("return 0");
-            0
-        } else {
-            // println!("return cursor");
-
-            let b: usize;
+        }
+    }
 
-            {
-                b = * self.cursor.lock().unwrap();
+    /// One round of maintenance
+    /// 一轮维护
+    async fn maintain(&self) {
+        self.probe_hosts().await;
+        if self.config.health_check_interval != 0 {
+            let interval =
+                std::time::Duration::from_millis(self.config.health_check_interval as u64);
+            let due = {
+                let mut last = self.last_health_check.lock().unwrap();
+                if last.elapsed() >= interval {
+                    *last = std::time::Instant::now();
+                    true
+                } else {
+                    false
+                }
+            };
+            if due {
+                self.check_idle_connections().await;
             }
+        }
+        let min = self.config.min_connection_pool_size as usize;
+        if self.config.idle_time != 0 {
+            let idle_time = std::time::Duration::from_millis(self.config.idle_time as u64);
+            let mut evicted = Vec::new();
             {
-                let mut cursor_add = self.cursor.lock().unwrap();
-                * cursor_add += 1 as usize;
+                let mut conns = self.conns.lock().unwrap();
+                let mut remain = self.conns_count.load(Ordering::Acquire);
+                // The oldest idle connection is in the front
+                while remain > min {
+                    match conns.front() {
+                        Some(idle) if idle.since.elapsed() >= idle_time => {
+                            evicted.push(conns.pop_front().unwrap().conn);
+                            remain -= 1;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            for conn in evicted {
+                self.discard(conn);
             }
-            
-            b
+        }
+        let count = self.conns_count.load(Ordering::Acquire);
+        if count < min {
+            self.new_connection((min - count) as u32).await;
         }
     }
 
+    /// Create at most `inc` idle connections concurrently,
+    /// limited by `max_connection_pool_size` and `connect_concurrency`
+    /// 并发创建至多 `inc` 个空闲连接，受 `max_connection_pool_size` 和 `connect_concurrency` 限制
+    pub async fn new_connection(&self, inc: u32) {
+        assert!(inc != 0);
+        // Reserve the slots first, so concurrent callers never overshoot the limit
+        let mut reserved = 0;
+        while reserved < inc && self.reserve() {
+            reserved += 1;
+        }
+        let concurrency = if self.config.connect_concurrency == 0 {
+            10
+        } else {
+            self.config.connect_concurrency as usize
+        };
+        futures::stream::iter(0..reserved)
+            .for_each_concurrent(concurrency, |_| async move {
+                // Try each address at most once for the slot
+                for _ in 0..self.config.addresses.len() {
+                    let cursor = self.cursor();
+                    if let Ok(conn) = self.connect_to(cursor).await {
+                        // append the conn to the conenction list
+                        self.conns.lock().unwrap().push_back(IdleConnection::new(conn));
+                        return;
+                    }
+                }
+                // Can't connect to any address, give up the slot
+                self.unreserve();
+            })
+            .await;
+    }
+
+    // choose the server address by load balancer, skip the hosts in backoff period
+    // 通过负载均衡器选择服务器地址，跳过处于退避期的地址
+    fn cursor(&self) -> usize {
+        let hosts = self.hosts.lock().unwrap();
+        let now = std::time::Instant::now();
+        let up: Vec<usize> = (0..hosts.len()).filter(|i| hosts[*i].is_up(now)).collect();
+        if up.is_empty() {
+            // All hosts are down, try the one recovering first
+            return hosts
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, host)| host.down_until)
+                .map(|(cursor, _)| cursor)
+                .unwrap_or(0);
+        }
+        let infos: Vec<HostInfo> = up
+            .iter()
+            .map(|i| HostInfo {
+                address: hosts[*i].address.as_str(),
+                sessions: hosts[*i].sessions,
+                weight: hosts[*i].weight,
+            })
+            .collect();
+        let chosen = self.balancer.select(&infos);
+        up[chosen.min(up.len() - 1)]
+    }
+
+    /// Get the configuration of pool
+    #[inline]
+    pub(crate) fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
     pub fn get_config(&self){
         println!("{:?}", self.config);
     }
-}
\ No newline at end of file
+}
+impl Drop for ConnectionPool_nebula {
+    fn drop(&mut self) {
+        if let Some(handle) = self.maintenance.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
@@ -0,0 +1,101 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use crate::error::{Error, Result};
+
+/// Quote the name of space, tag, edge, property or index with backticks.
+/// The backtick can't be escaped in the name, so the name with backtick is rejected,
+/// and so is the empty name
+/// 用反引号包裹空间、标签、边、属性或索引的名称，名称中的反引号无法转义，
+/// 因此拒绝包含反引号的名称和空名称
+pub fn quote_identifier(name: &str) -> Result<String> {
+    if name.is_empty() {
+        return Err(Error::Statement(String::from("the name is empty")));
+    }
+    if name.contains('`') {
+        return Err(Error::Statement(format!("the name `{}` contains backtick", name)));
+    }
+    Ok(format!("`{}`", name))
+}
+
+/// Quote the string literal with double quotes, e.g. vid, property value or comment
+/// 用双引号包裹字符串字面量，例如点 ID、属性值或注释
+pub fn quote_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The reference `$name` of variable. The name can't be quoted, so only the name of letters,
+/// digits and `_` which doesn't start with a digit is accepted
+/// 变量引用 `$name`，名称无法引用，仅接受由字母、数字和 `_` 组成且不以数字开头的名称
+pub fn variable(name: &str) -> Result<String> {
+    let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.chars().next().is_some_and(|c| !c.is_ascii_digit());
+    if !valid {
+        return Err(Error::Statement(format!("invalid variable name `{}`", name)));
+    }
+    Ok(format!("${}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("player").unwrap(), "`player`");
+        assert_eq!(quote_identifier("my space").unwrap(), "`my space`");
+        assert_eq!(quote_identifier("a\\b").unwrap(), "`a\\b`");
+        assert_eq!(quote_identifier("a\"b;").unwrap(), "`a\"b;`");
+        assert_eq!(quote_identifier("球员").unwrap(), "`球员`");
+        assert!(matches!(quote_identifier("a`b"), Err(Error::Statement(_))));
+        assert!(matches!(
+            quote_identifier("`; DROP SPACE s; `"),
+            Err(Error::Statement(_))
+        ));
+        assert!(matches!(quote_identifier(""), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!(quote_string("Tim Duncan"), "\"Tim Duncan\"");
+        assert_eq!(quote_string("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(
+            quote_string("\"):(1); DROP SPACE s; ("),
+            "\"\\\"):(1); DROP SPACE s; (\""
+        );
+        assert_eq!(quote_string("C:\\path\\"), "\"C:\\\\path\\\\\"");
+        assert_eq!(quote_string("a\\\"b"), "\"a\\\\\\\"b\"");
+        assert_eq!(quote_string("a`b'c"), "\"a`b'c\"");
+        assert_eq!(quote_string("line1\nline2\r\tend"), "\"line1\\nline2\\r\\tend\"");
+        assert_eq!(quote_string("姚明 🏀 é"), "\"姚明 🏀 é\"");
+        assert_eq!(quote_string(""), "\"\"");
+    }
+
+    #[test]
+    fn test_variable() {
+        assert_eq!(variable("var").unwrap(), "$var");
+        assert_eq!(variable("a_1").unwrap(), "$a_1");
+        assert_eq!(variable("_A").unwrap(), "$_A");
+        for name in ["", "1a", "a-b", "a b", "a;`\"", "变量"] {
+            assert!(matches!(variable(name), Err(Error::Statement(_))), "{}", name);
+        }
+    }
+}
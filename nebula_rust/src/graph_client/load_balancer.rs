@@ -0,0 +1,203 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rand::Rng;
+
+/// The snapshot of one available graph server offered to load balancer
+/// 提供给负载均衡器的可用图服务快照
+#[derive(Debug, Clone)]
+pub struct HostInfo<'a> {
+    /// address of graph server
+    pub address: &'a str,
+    /// count of sessions holding connections to this server
+    pub sessions: usize,
+    /// weight configured by `PoolConfig::weighted_address`, 1 by default
+    pub weight: u32,
+}
+
+/// Strategy to choose the graph server for a new session
+/// 为新会话选择图服务的策略
+pub trait LoadBalancer: Send + Sync + std::fmt::Debug {
+    /// Return the index of the chosen one in `hosts`, which is never empty
+    /// 返回选中服务在 `hosts` 中的下标，`hosts` 不会为空
+    fn select(&self, hosts: &[HostInfo]) -> usize;
+}
+
+/// Choose the servers in turn
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    cursor: AtomicUsize,
+}
+
+impl LoadBalancer for RoundRobin {
+    fn select(&self, hosts: &[HostInfo]) -> usize {
+        self.cursor.fetch_add(1, Ordering::Relaxed) % hosts.len()
+    }
+}
+
+/// Choose the server randomly
+#[derive(Debug, Default)]
+pub struct Random;
+
+impl LoadBalancer for Random {
+    fn select(&self, hosts: &[HostInfo]) -> usize {
+        rand::thread_rng().gen_range(0..hosts.len())
+    }
+}
+
+/// Choose the server with least sessions, in turn if tied
+#[derive(Debug, Default)]
+pub struct LeastSessions {
+    cursor: AtomicUsize,
+}
+
+impl LoadBalancer for LeastSessions {
+    fn select(&self, hosts: &[HostInfo]) -> usize {
+        let start = self.cursor.fetch_add(1, Ordering::Relaxed);
+        (0..hosts.len())
+            .map(|i| (start + i) % hosts.len())
+            .min_by_key(|i| hosts[*i].sessions)
+            .unwrap_or(0)
+    }
+}
+
+/// Choose the server randomly in proportion to its weight
+#[derive(Debug, Default)]
+pub struct Weighted;
+
+impl LoadBalancer for Weighted {
+    fn select(&self, hosts: &[HostInfo]) -> usize {
+        let total: u64 = hosts.iter().map(|host| host.weight as u64).sum();
+        if total == 0 {
+            return rand::thread_rng().gen_range(0..hosts.len());
+        }
+        let mut point = rand::thread_rng().gen_range(0..total);
+        for (i, host) in hosts.iter().enumerate() {
+            if point < host.weight as u64 {
+                return i;
+            }
+            point -= host.weight as u64;
+        }
+        hosts.len() - 1
+    }
+}
+
+/// The load balancing strategy selected in `PoolConfig`
+/// 在 `PoolConfig` 中选择的负载均衡策略
+#[derive(Debug, Clone, Default)]
+pub enum LoadBalancePolicy {
+    #[default]
+    RoundRobin,
+    Random,
+    LeastSessions,
+    Weighted,
+    Custom(Arc<dyn LoadBalancer>),
+}
+
+impl LoadBalancePolicy {
+    /// Construct the load balancer of this policy
+    pub fn build(&self) -> Arc<dyn LoadBalancer> {
+        match self {
+            LoadBalancePolicy::RoundRobin => Arc::new(RoundRobin::default()),
+            LoadBalancePolicy::Random => Arc::new(Random),
+            LoadBalancePolicy::LeastSessions => Arc::new(LeastSessions::default()),
+            LoadBalancePolicy::Weighted => Arc::new(Weighted),
+            LoadBalancePolicy::Custom(balancer) => Arc::clone(balancer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_infos(sessions: &[usize], weights: &[u32]) -> Vec<HostInfo<'static>> {
+        sessions
+            .iter()
+            .zip(weights)
+            .map(|(sessions, weight)| HostInfo {
+                address: "127.0.0.1:9669",
+                sessions: *sessions,
+                weight: *weight,
+            })
+            .collect()
+    }
+
+    fn tally(balancer: &dyn LoadBalancer, hosts: &[HostInfo], rounds: usize) -> Vec<usize> {
+        let mut counts = vec![0; hosts.len()];
+        for _ in 0..rounds {
+            let chosen = balancer.select(hosts);
+            assert!(chosen < hosts.len());
+            counts[chosen] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let balancer = RoundRobin::default();
+        let hosts = host_infos(&[0, 0, 0], &[1, 1, 1]);
+        let chosen: Vec<usize> = (0..7).map(|_| balancer.select(&hosts)).collect();
+        assert_eq!(chosen, vec![0, 1, 2, 0, 1, 2, 0]);
+        // The cursor keeps going when the available hosts change
+        assert_eq!(balancer.select(&hosts[..2]), 1);
+    }
+
+    #[test]
+    fn test_random() {
+        let hosts = host_infos(&[0, 0, 0], &[1, 1, 1]);
+        let counts = tally(&Random, &hosts, 3000);
+        assert!(counts.iter().all(|count| *count > 0));
+        assert_eq!(Random.select(&hosts[..1]), 0);
+    }
+
+    #[test]
+    fn test_least_sessions() {
+        let balancer = LeastSessions::default();
+        let hosts = host_infos(&[3, 1, 2], &[1, 1, 1]);
+        for _ in 0..5 {
+            assert_eq!(balancer.select(&hosts), 1);
+        }
+        // Ties are broken in turn
+        let hosts = host_infos(&[2, 1, 1], &[1, 1, 1]);
+        let chosen: Vec<usize> = (0..4).map(|_| balancer.select(&hosts)).collect();
+        assert!(chosen.iter().all(|chosen| *chosen != 0));
+        assert!(chosen.contains(&1) && chosen.contains(&2));
+    }
+
+    #[test]
+    fn test_weighted() {
+        let hosts = host_infos(&[0, 0, 0], &[0, 5, 0]);
+        for _ in 0..100 {
+            assert_eq!(Weighted.select(&hosts), 1);
+        }
+
+        let hosts = host_infos(&[0, 0], &[1, 3]);
+        let counts = tally(&Weighted, &hosts, 4000);
+        // 1000 and 3000 expected
+        assert!(counts[0] > 700 && counts[0] < 1300, "{:?}", counts);
+
+        // All zero weights fall back to random
+        let hosts = host_infos(&[0, 0], &[0, 0]);
+        let counts = tally(&Weighted, &hosts, 1000);
+        assert!(counts.iter().all(|count| *count > 0));
+    }
+
+    #[test]
+    fn test_policy_build() {
+        let hosts = host_infos(&[0, 0], &[1, 1]);
+        let balancer = LoadBalancePolicy::default().build();
+        assert_eq!(balancer.select(&hosts), 0);
+        assert_eq!(balancer.select(&hosts), 1);
+
+        let custom: Arc<dyn LoadBalancer> = Arc::new(RoundRobin::default());
+        let built = LoadBalancePolicy::Custom(Arc::clone(&custom)).build();
+        assert!(Arc::ptr_eq(&custom, &built));
+    }
+}
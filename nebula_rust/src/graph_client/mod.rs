@@ -6,7 +6,12 @@
 
 pub mod connection;
 pub mod connection_pool;
+pub mod escape;
+pub mod load_balancer;
+pub mod nebula_url;
 pub mod pool_config;
+pub mod query;
+pub mod retry_policy;
 pub mod session;
 pub mod nebula_schema;
 mod transport_response_handler;
@@ -1,5 +1,14 @@
 use std::collections::HashMap;
 
+use common::types::Value;
+
+use crate::error::{Error, Result};
+use crate::graph_client::escape;
+use crate::graph_client::query::{Fetch, Sentence, Source};
+use crate::value::result_set::ResultSet;
+
+pub use nebula_rust_derive::{NebulaEdge, NebulaTag};
+
 /// contains all properties of both tag and edge
 pub struct Tag{
     property_name: String,
@@ -24,10 +33,9 @@ impl Tag {
             defaults: defaults.to_string(), 
             comment: comment.to_string() }
     }
-    pub fn to_string(&self)-> String{
-        let mut line = "`".to_string();
-        line += self.property_name.as_str();
-        line += "` ";
+    pub fn to_string(&self)-> Result<String>{
+        let mut line = escape::quote_identifier(&self.property_name)?;
+        line += " ";
         line += self.data_type.to_string().as_str();
         line += " ";
         if self.allow_null{
@@ -36,25 +44,26 @@ impl Tag {
             line += "NOT NULL ";
         }
         if self.defaults!="".to_string(){
-            line += "DEFAULT \"";
-            line += self.defaults.as_str();
-            line += "\" ";
+            line += "DEFAULT ";
+            line += self.data_type.default_literal(&self.defaults)?.as_str();
+            line += " ";
         }
         if self.comment!="".to_string(){
-            line += "COMMENT \"";
-            line += self.comment.as_str();
-            line += "\" ";
+            line += "COMMENT ";
+            line += escape::quote_string(&self.comment).as_str();
+            line += " ";
         }
-        line.to_string()
+        Ok(line)
     }
 }
 
-/// frequently-used data type in NebulaGraph
+/// data type of property in NebulaGraph
 pub enum DataType {
     Int,
     Bool,
     String,
-    FixedString,
+    /// string with the max length
+    FixedString(u32),
     Double,
     Int32,
     Int16,
@@ -62,6 +71,19 @@ pub enum DataType {
     Float,
     Date,
     Time,
+    DateTime,
+    Timestamp,
+    Duration,
+    Geography(GeoShape),
+}
+
+/// shape of geography
+pub enum GeoShape {
+    /// any shape
+    Any,
+    Point,
+    LineString,
+    Polygon,
 }
 
 impl DataType {
@@ -70,7 +92,7 @@ impl DataType {
             DataType::Int => String::from("int"),
             DataType::Bool => String::from("bool"),
             DataType::String => String::from("string"),
-            DataType::FixedString => String::from("fixed_string"),
+            DataType::FixedString(len) => format!("fixed_string({})", len),
             DataType::Double => String::from("double"),
             DataType::Int32 => String::from("int32"),
             DataType::Int16 => String::from("int16"),
@@ -78,11 +100,117 @@ impl DataType {
             DataType::Float => String::from("float"),
             DataType::Date => String::from("date"),
             DataType::Time => String::from("time"),
+            DataType::DateTime => String::from("datetime"),
+            DataType::Timestamp => String::from("timestamp"),
+            DataType::Duration => String::from("duration"),
+            DataType::Geography(GeoShape::Any) => String::from("geography"),
+            DataType::Geography(GeoShape::Point) => String::from("geography(point)"),
+            DataType::Geography(GeoShape::LineString) => String::from("geography(linestring)"),
+            DataType::Geography(GeoShape::Polygon) => String::from("geography(polygon)"),
+        }
+    }
+
+    /// Render the default value as literal of the type, e.g. `18`, `"Tom"`, `date("2021-01-02")`,
+    /// `duration({days: 1})` of `{days: 1}` or `ST_GeogFromText("POINT(1 2)")`. A call without arguments
+    /// like `now()` is kept for the types other than string. The number, bool, timestamp and duration
+    /// which isn't a valid literal of the type fails.
+    fn default_literal(&self, defaults: &str) -> Result<String> {
+        let quoted = escape::quote_string(defaults);
+        match self {
+            DataType::String | DataType::FixedString(_) => return Ok(quoted),
+            _ if is_call(defaults) => return Ok(defaults.to_string()),
+            DataType::Duration => return duration_literal(defaults),
+            _ => {}
+        }
+        let is_datetime = !defaults.is_empty()
+            && defaults.chars().all(|c| c.is_ascii_digit() || "-:T. ".contains(c));
+        Ok(match self {
+            DataType::Int | DataType::Int32 | DataType::Int16 | DataType::Int8
+                if defaults.parse::<i64>().is_ok() =>
+            {
+                defaults.to_string()
+            }
+            DataType::Double | DataType::Float
+                if defaults.parse::<f64>().is_ok_and(f64::is_finite) =>
+            {
+                defaults.to_string()
+            }
+            DataType::Bool if defaults.eq_ignore_ascii_case("true") || defaults.eq_ignore_ascii_case("false") => {
+                defaults.to_ascii_lowercase()
+            }
+            DataType::Timestamp if defaults.parse::<i64>().is_ok() => defaults.to_string(),
+            DataType::Date => format!("date({})", quoted),
+            DataType::Time => format!("time({})", quoted),
+            DataType::DateTime => format!("datetime({})", quoted),
+            DataType::Timestamp if is_datetime => format!("timestamp({})", quoted),
+            DataType::Geography(_) => format!("ST_GeogFromText({})", quoted),
+            _ => {
+                return Err(Error::Statement(format!(
+                    "invalid default value `{}` of {}",
+                    defaults,
+                    self.to_string()
+                )))
+            }
+        })
+    }
+}
+
+/// `duration({days: 1, hours: 2})` of the map `{days: 1, hours: 2}`
+fn duration_literal(defaults: &str) -> Result<String> {
+    const UNITS: [&str; 7] = ["years", "months", "days", "hours", "minutes", "seconds", "microseconds"];
+    let invalid = || Error::Statement(format!("invalid duration `{}`, expect a map like {{days: 1}}", defaults));
+    let fields = defaults
+        .trim()
+        .strip_prefix('{')
+        .and_then(|fields| fields.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    let fields = fields
+        .split(',')
+        .map(|field| {
+            let (unit, value) = field.split_once(':').ok_or_else(invalid)?;
+            let unit = unit.trim().to_ascii_lowercase();
+            let value = value.trim();
+            if !UNITS.contains(&unit.as_str()) || value.parse::<i64>().is_err() {
+                return Err(invalid());
+            }
+            Ok(format!("{}: {}", unit, value))
+        })
+        .collect::<Result<Vec<String>>>()?;
+    Ok(format!("duration({{{}}})", fields.join(", ")))
+}
+
+/// Whether it's a call without arguments, e.g. `now()`
+fn is_call(s: &str) -> bool {
+    match s.strip_suffix("()") {
+        Some(name) => {
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// Time to live of the vertices or edges, they expire `duration` seconds after the `col` property
+/// 点或边的存活时间，在 `col` 属性的时间 `duration` 秒后过期
+#[derive(Debug, Clone)]
+pub struct Ttl {
+    /// seconds to live, 0 means never expire
+    pub duration: u64,
+    /// the int or timestamp property as the start time
+    pub col: String,
+}
+
+impl Ttl {
+    pub fn new(duration: u64, col: &str) -> Self {
+        Ttl {
+            duration,
+            col: col.to_string(),
         }
     }
 }
 
 /// tag or edge
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ColType {
     Tag,
     Edge,
@@ -97,18 +225,86 @@ impl ColType {
     }
 }
 
+/// Interval to retry, 100ms doubles up to 1.6s
+fn schema_poll_interval(attempt: u32) -> std::time::Duration {
+    std::time::Duration::from_millis(100 << attempt.min(4))
+}
+
+/// Run `f` again with backoff while it fails by `Error::is_schema_missing`,
+/// graphd and storaged load the new tag or edge from metad at the next heartbeat.
+/// Return the last error if it's still missing after `timeout` ms
+pub(crate) async fn retry_while_schema_missing<T, F, Fut>(timeout: u32, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(timeout as u64);
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Err(e) if e.is_schema_missing() => {
+                let now = tokio::time::Instant::now();
+                if now >= deadline {
+                    return Err(e);
+                }
+                tokio::time::sleep(schema_poll_interval(attempt).min(deadline - now)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Wait until graphd and storaged can use the tag or edge, `query` runs the statement in the space.
+/// `SHOW TAGS/EDGES` is answered by metad before the others load the schema, so it fetches
+/// a vertex or edge, which needn't exist, until the tag or edge isn't missing.
+/// Fail with `Error::Timeout` if it's still missing after `timeout` ms
+pub(crate) async fn wait_for_schema<F, Fut>(
+    space_name: &str,
+    name: &str,
+    col_type: ColType,
+    timeout: u32,
+    query: F,
+) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<ResultSet>>,
+{
+    let space = query(format!("DESCRIBE SPACE {};", escape::quote_identifier(space_name)?)).await?;
+    let int_vid = space
+        .row(0)
+        .and_then(|row| row.get_as::<String>("Vid Type").ok())
+        .is_some_and(|vid_type| vid_type.starts_with("INT"));
+    let vid = if int_vid { Value::from(0i64) } else { Value::from("0") };
+    let probe = match col_type {
+        ColType::Tag => Fetch::vertices(&[name], Source::vids([vid])).yield_as("vertex", "v").statement()?,
+        ColType::Edge => Fetch::edges(name).edge(vid.clone(), vid, 0).yield_as("edge", "e").statement()?,
+    };
+    match retry_while_schema_missing(timeout, || query(probe.clone())).await {
+        Err(e) if e.is_schema_missing() => Err(Error::Timeout(format!(
+            "wait for {} `{}` in space `{}`: {}",
+            col_type.to_string(),
+            name,
+            space_name,
+            e
+        ))),
+        result => result.map(|_| ()),
+    }
+}
+
 /// query of inserting tag
 pub struct InsertTagQuery{
     pub space_name: String, 
     pub tag_name: String, 
-    pub kv: HashMap<String, String>, 
+    /// property name to value
+    pub kv: HashMap<String, Value>, 
     pub vid: String,
 }
 impl InsertTagQuery{
     pub fn new(
         space_name: String, 
         tag_name: String, 
-        kv: HashMap<String, String>, 
+        kv: HashMap<String, Value>, 
         vid: String,
     ) -> Self{
         InsertTagQuery{
@@ -118,12 +314,12 @@ impl InsertTagQuery{
             vid,
         }
     }
-    pub fn to_string(&self)-> String{
-        let mut query = String::from("use ");
-        query += self.space_name.as_str();
+    pub fn to_string(&self)-> Result<String>{
+        let mut query = String::from("USE ");
+        query += escape::quote_identifier(&self.space_name)?.as_str();
         query += "; ";
         query += "INSERT VERTEX ";
-        query += self.tag_name.as_str();
+        query += escape::quote_identifier(&self.tag_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -132,18 +328,18 @@ impl InsertTagQuery{
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k)?.as_str();
+            values += value_literal(v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += self.vid.as_str();
-        query += "\":";
+        query += " VALUES ";
+        query += escape::quote_string(&self.vid).as_str();
+        query += ":";
         query += values.as_str();
         query += ";";
-        query
+        Ok(query)
     }
 }
 
@@ -152,7 +348,8 @@ impl InsertTagQuery{
 pub struct InsertEdgeQueryWithRank{
     pub space_name: String, 
     pub edge_name: String, 
-    pub kv: HashMap<String, String>, 
+    /// property name to value
+    pub kv: HashMap<String, Value>, 
     pub from_vertex: String, 
     pub to_vertex: String,
     pub rank: i64,
@@ -161,7 +358,7 @@ impl InsertEdgeQueryWithRank{
     pub fn new(
         space_name: String, 
         edge_name: String, 
-        kv: HashMap<String, String>, 
+        kv: HashMap<String, Value>, 
         from_vertex: String, 
         to_vertex: String,
         rank: i64,
@@ -175,12 +372,12 @@ impl InsertEdgeQueryWithRank{
             rank,
         }
     }
-    pub fn to_string(&self)-> String{
-        let mut query = String::from("use ");
-        query += self.space_name.as_str();
+    pub fn to_string(&self)-> Result<String>{
+        let mut query = String::from("USE ");
+        query += escape::quote_identifier(&self.space_name)?.as_str();
         query += "; ";
         query += "INSERT EDGE IF NOT EXISTS ";
-        query += self.edge_name.as_str();
+        query += escape::quote_identifier(&self.edge_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -189,22 +386,1252 @@ impl InsertEdgeQueryWithRank{
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k)?.as_str();
+            values += value_literal(v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += self.from_vertex.as_str();
-        query += "\" -> \"";
-        query += self.to_vertex.as_str();
-        query += "\"@";
+        query += " VALUES ";
+        query += escape::quote_string(&self.from_vertex).as_str();
+        query += " -> ";
+        query += escape::quote_string(&self.to_vertex).as_str();
+        query += "@";
         query += self.rank.to_string().as_str();
         query += ":";
         query += values.as_str();
         query += ";";
-        query
+        Ok(query)
+    }
+}
+
+
+
+/// The vertex or edge to update
+pub enum UpdateTarget {
+    Vertex {
+        tag_name: String,
+        vid: Value,
+    },
+    Edge {
+        edge_name: String,
+        src: Value,
+        dst: Value,
+        rank: i64,
+    },
+}
+
+/// The new value of property in `SET`
+#[derive(Debug, Clone)]
+pub enum UpdateValue {
+    /// rendered as literal
+    Value(Value),
+    /// nGQL expression, not escaped
+    Expr(String),
+}
+
+/// query of updating or upserting the properties of vertex or edge
+/// 更新或插入更新点、边属性的查询
+pub struct UpdateQuery {
+    pub space_name: String,
+    pub target: UpdateTarget,
+    /// `UPSERT` instead of `UPDATE`, insert if the vertex or edge doesn't exist
+    pub upsert: bool,
+    /// property name to the new value
+    pub set: Vec<(String, UpdateValue)>,
+    /// nGQL condition in `WHEN`, update only if it's true
+    pub when: Option<String>,
+    /// property name and optional alias in `YIELD`
+    pub yields: Vec<(String, Option<String>)>,
+}
+
+impl UpdateQuery {
+    /// `UPDATE VERTEX ON tag vid`
+    pub fn vertex<V: Into<Value>>(space_name: &str, tag_name: &str, vid: V) -> Self {
+        Self::new(
+            space_name,
+            UpdateTarget::Vertex {
+                tag_name: tag_name.to_string(),
+                vid: vid.into(),
+            },
+        )
+    }
+
+    /// `UPDATE EDGE ON edge src -> dst@rank`
+    pub fn edge<V: Into<Value>>(space_name: &str, edge_name: &str, src: V, dst: V, rank: i64) -> Self {
+        Self::new(
+            space_name,
+            UpdateTarget::Edge {
+                edge_name: edge_name.to_string(),
+                src: src.into(),
+                dst: dst.into(),
+                rank,
+            },
+        )
+    }
+
+    fn new(space_name: &str, target: UpdateTarget) -> Self {
+        UpdateQuery {
+            space_name: space_name.to_string(),
+            target,
+            upsert: false,
+            set: Vec::new(),
+            when: None,
+            yields: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn upsert(&mut self, upsert: bool) -> &mut Self {
+        self.upsert = upsert;
+        self
+    }
+
+    /// Set the property to the value
+    #[inline]
+    pub fn set<V: Into<Value>>(&mut self, property: &str, value: V) -> &mut Self {
+        self.set.push((property.to_string(), UpdateValue::Value(value.into())));
+        self
+    }
+
+    /// Set the property to the nGQL expression, e.g. `age + 1`, which is not escaped
+    #[inline]
+    pub fn set_expr(&mut self, property: &str, expr: &str) -> &mut Self {
+        self.set.push((property.to_string(), UpdateValue::Expr(expr.to_string())));
+        self
+    }
+
+    /// Update only if the nGQL condition is true, e.g. `age > 18`, which is not escaped
+    #[inline]
+    pub fn when(&mut self, condition: &str) -> &mut Self {
+        self.when = Some(condition.to_string());
+        self
+    }
+
+    /// Return the property after updating
+    #[inline]
+    pub fn yield_prop(&mut self, property: &str) -> &mut Self {
+        self.yields.push((property.to_string(), None));
+        self
+    }
+
+    /// Return the property after updating as the column `alias`
+    #[inline]
+    pub fn yield_prop_as(&mut self, property: &str, alias: &str) -> &mut Self {
+        self.yields.push((property.to_string(), Some(alias.to_string())));
+        self
+    }
+
+    /// Build the statement, which runs in the current space of session, fail if nothing is set
+    /// 构造语句，语句在会话的当前图空间中执行，未设置任何属性时返回错误
+    pub fn statement(&self) -> Result<String> {
+        if self.set.is_empty() {
+            return Err(Error::Statement(String::from("nothing to set in UPDATE")));
+        }
+        let mut query = String::from(if self.upsert { "UPSERT " } else { "UPDATE " });
+        match &self.target {
+            UpdateTarget::Vertex { tag_name, vid } => {
+                query += "VERTEX ON ";
+                query += escape::quote_identifier(tag_name)?.as_str();
+                query += " ";
+                query += value_literal(vid)?.as_str();
+            }
+            UpdateTarget::Edge {
+                edge_name,
+                src,
+                dst,
+                rank,
+            } => {
+                query += "EDGE ON ";
+                query += escape::quote_identifier(edge_name)?.as_str();
+                query += " ";
+                query += value_literal(src)?.as_str();
+                query += " -> ";
+                query += value_literal(dst)?.as_str();
+                query += "@";
+                query += rank.to_string().as_str();
+            }
+        }
+        let set = self
+            .set
+            .iter()
+            .map(|(property, value)| {
+                let value = match value {
+                    UpdateValue::Value(value) => value_literal(value)?,
+                    UpdateValue::Expr(expr) => expr.clone(),
+                };
+                Ok(format!("{} = {}", escape::quote_identifier(property)?, value))
+            })
+            .collect::<Result<Vec<String>>>()?;
+        query += " SET ";
+        query += set.join(", ").as_str();
+        if let Some(condition) = &self.when {
+            query += " WHEN ";
+            query += condition.as_str();
+        }
+        if !self.yields.is_empty() {
+            let yields = self
+                .yields
+                .iter()
+                .map(|(property, alias)| match alias {
+                    Some(alias) => Ok(format!(
+                        "{} AS {}",
+                        escape::quote_identifier(property)?,
+                        escape::quote_identifier(alias)?
+                    )),
+                    None => escape::quote_identifier(property),
+                })
+                .collect::<Result<Vec<String>>>()?;
+            query += " YIELD ";
+            query += yields.join(", ").as_str();
+        }
+        query += ";";
+        Ok(query)
+    }
+}
+
+/// query of deleting vertices, with their edges if `with_edge`
+/// 删除点的查询，`with_edge` 时同时删除其关联的边
+pub struct DeleteVertexQuery {
+    pub space_name: String,
+    pub vids: Vec<Value>,
+    pub with_edge: bool,
+}
+
+impl DeleteVertexQuery {
+    pub fn new(space_name: &str, with_edge: bool) -> Self {
+        DeleteVertexQuery {
+            space_name: space_name.to_string(),
+            vids: Vec::new(),
+            with_edge,
+        }
+    }
+
+    #[inline]
+    pub fn vid<V: Into<Value>>(&mut self, vid: V) -> &mut Self {
+        self.vids.push(vid.into());
+        self
+    }
+
+    /// Split into `DELETE VERTEX` statements within the limit
+    /// 按限制拆分为多条 `DELETE VERTEX` 语句
+    pub fn batches(&self, limit: &BatchLimit) -> Result<Vec<StatementBatch>> {
+        let footer = if self.with_edge { " WITH EDGE" } else { "" };
+        let rows = self.vids.iter().map(value_literal).collect::<Result<_>>()?;
+        let mut batches = Vec::new();
+        pack(&self.space_name, "DELETE VERTEX ", footer, rows, limit, &mut batches);
+        Ok(batches)
+    }
+}
+
+/// query of deleting edges of one edge type
+/// 删除同一边类型的多条边的查询
+pub struct DeleteEdgeQuery {
+    pub space_name: String,
+    pub edge_name: String,
+    /// src, dst and rank of edges
+    pub edges: Vec<(Value, Value, i64)>,
+}
+
+impl DeleteEdgeQuery {
+    pub fn new(space_name: &str, edge_name: &str) -> Self {
+        DeleteEdgeQuery {
+            space_name: space_name.to_string(),
+            edge_name: edge_name.to_string(),
+            edges: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn edge<V: Into<Value>>(&mut self, src: V, dst: V, rank: i64) -> &mut Self {
+        self.edges.push((src.into(), dst.into(), rank));
+        self
+    }
+
+    /// Split into `DELETE EDGE` statements within the limit
+    /// 按限制拆分为多条 `DELETE EDGE` 语句
+    pub fn batches(&self, limit: &BatchLimit) -> Result<Vec<StatementBatch>> {
+        let header = format!("DELETE EDGE {} ", escape::quote_identifier(&self.edge_name)?);
+        let rows = self
+            .edges
+            .iter()
+            .map(|(src, dst, rank)| Ok(format!("{} -> {}@{}", value_literal(src)?, value_literal(dst)?, rank)))
+            .collect::<Result<_>>()?;
+        let mut batches = Vec::new();
+        pack(&self.space_name, &header, "", rows, limit, &mut batches);
+        Ok(batches)
+    }
+}
+
+/// query of deleting tags from vertices, the vertices are kept
+/// 删除点上标签的查询，点本身保留
+pub struct DeleteTagQuery {
+    pub space_name: String,
+    /// names of tags to delete, empty means all tags
+    pub tag_names: Vec<String>,
+    pub vids: Vec<Value>,
+}
+
+impl DeleteTagQuery {
+    /// Delete the tags, all tags if `tag_names` is empty
+    pub fn new(space_name: &str, tag_names: &[&str]) -> Self {
+        DeleteTagQuery {
+            space_name: space_name.to_string(),
+            tag_names: tag_names.iter().map(|name| name.to_string()).collect(),
+            vids: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn vid<V: Into<Value>>(&mut self, vid: V) -> &mut Self {
+        self.vids.push(vid.into());
+        self
+    }
+
+    /// Split into `DELETE TAG` statements within the limit
+    /// 按限制拆分为多条 `DELETE TAG` 语句
+    pub fn batches(&self, limit: &BatchLimit) -> Result<Vec<StatementBatch>> {
+        let tags = if self.tag_names.is_empty() {
+            String::from("*")
+        } else {
+            let names = self
+                .tag_names
+                .iter()
+                .map(|name| escape::quote_identifier(name))
+                .collect::<Result<Vec<String>>>()?;
+            names.join(", ")
+        };
+        let header = format!("DELETE TAG {} FROM ", tags);
+        let rows = self.vids.iter().map(value_literal).collect::<Result<_>>()?;
+        let mut batches = Vec::new();
+        pack(&self.space_name, &header, "", rows, limit, &mut batches);
+        Ok(batches)
+    }
+}
+
+/// A struct mapped to a tag, usually by `#[derive(NebulaTag)]`.
+/// The statements run in the current space of session
+/// 映射为标签的结构体，通常通过 `#[derive(NebulaTag)]` 实现，语句在会话的当前图空间中执行
+pub trait NebulaTag {
+    fn tag_name() -> &'static str;
+
+    fn tag_comment() -> &'static str {
+        ""
+    }
+
+    fn tag_ttl() -> Option<Ttl> {
+        None
+    }
+
+    /// Definition of the properties in order
+    fn properties() -> Vec<Tag>;
+
+    /// Names of the properties in order
+    fn property_names() -> Vec<&'static str>;
+
+    fn vid(&self) -> Value;
+
+    /// Values of the properties in order
+    fn property_values(&self) -> Vec<Value>;
+
+    /// `CREATE TAG IF NOT EXISTS` statement of the tag
+    fn create_statement() -> Result<String> {
+        create_schema_statement(
+            ColType::Tag,
+            Self::tag_name(),
+            Self::tag_comment(),
+            &Self::properties(),
+            Self::tag_ttl().as_ref(),
+        )
+    }
+
+    fn vertex_row(&self) -> VertexRow {
+        VertexRow {
+            vid: self.vid(),
+            values: self.property_values(),
+        }
+    }
+
+    /// `INSERT VERTEX` statement of this vertex
+    fn insert_statement(&self) -> Result<String> {
+        insert_vertex_statement(Self::tag_name(), &Self::property_names(), &[self.vertex_row()])
+    }
+}
+
+/// A struct mapped to an edge type, usually by `#[derive(NebulaEdge)]`.
+/// The statements run in the current space of session
+/// 映射为边类型的结构体，通常通过 `#[derive(NebulaEdge)]` 实现，语句在会话的当前图空间中执行
+pub trait NebulaEdge {
+    fn edge_name() -> &'static str;
+
+    fn edge_comment() -> &'static str {
+        ""
+    }
+
+    fn edge_ttl() -> Option<Ttl> {
+        None
+    }
+
+    /// Definition of the properties in order
+    fn properties() -> Vec<Tag>;
+
+    /// Names of the properties in order
+    fn property_names() -> Vec<&'static str>;
+
+    fn src(&self) -> Value;
+
+    fn dst(&self) -> Value;
+
+    fn rank(&self) -> i64 {
+        0
+    }
+
+    /// Values of the properties in order
+    fn property_values(&self) -> Vec<Value>;
+
+    /// `CREATE EDGE IF NOT EXISTS` statement of the edge type
+    fn create_statement() -> Result<String> {
+        create_schema_statement(
+            ColType::Edge,
+            Self::edge_name(),
+            Self::edge_comment(),
+            &Self::properties(),
+            Self::edge_ttl().as_ref(),
+        )
+    }
+
+    fn edge_row(&self) -> EdgeRow {
+        EdgeRow {
+            src: self.src(),
+            dst: self.dst(),
+            rank: self.rank(),
+            values: self.property_values(),
+        }
     }
+
+    /// `INSERT EDGE` statement of this edge
+    fn insert_statement(&self) -> Result<String> {
+        insert_edge_statement(Self::edge_name(), &Self::property_names(), &[self.edge_row()])
+    }
+}
+
+/// The vid and property values of one vertex to insert
+#[derive(Debug, Clone)]
+pub struct VertexRow {
+    pub vid: Value,
+    pub values: Vec<Value>,
+}
+
+impl VertexRow {
+    /// `vid:(values)` in `INSERT VERTEX`
+    fn literal(&self) -> Result<String> {
+        Ok(value_literal(&self.vid)? + ":" + value_list(&self.values)?.as_str())
+    }
+}
+
+/// The keys and property values of one edge to insert
+#[derive(Debug, Clone)]
+pub struct EdgeRow {
+    pub src: Value,
+    pub dst: Value,
+    pub rank: i64,
+    pub values: Vec<Value>,
+}
+
+impl EdgeRow {
+    /// `src -> dst@rank:(values)` in `INSERT EDGE`
+    fn literal(&self) -> Result<String> {
+        Ok(format!(
+            "{} -> {}@{}:{}",
+            value_literal(&self.src)?,
+            value_literal(&self.dst)?,
+            self.rank,
+            value_list(&self.values)?
+        ))
+    }
+}
+
+/// Build the `CREATE TAG/EDGE IF NOT EXISTS` statement
+/// 构造 `CREATE TAG/EDGE IF NOT EXISTS` 语句
+pub fn create_schema_statement(col_type: ColType, name: &str, comment: &str, properties: &[Tag], ttl: Option<&Ttl>) -> Result<String> {
+    let mut query = String::from("CREATE ");
+    query += col_type.to_string().as_str();
+    query += " IF NOT EXISTS ";
+    query += escape::quote_identifier(name)?.as_str();
+    query += " (";
+    for i in 0..properties.len() {
+        query += properties[i].to_string()?.as_str();
+        if i != properties.len() - 1 {
+            query += ",";
+        }
+    }
+    query += ")";
+    if let Some(ttl) = ttl {
+        query += " TTL_DURATION = ";
+        query += ttl.duration.to_string().as_str();
+        query += ", TTL_COL = ";
+        query += escape::quote_string(&ttl.col).as_str();
+    }
+    if !comment.is_empty() {
+        query += " COMMENT = ";
+        query += escape::quote_string(comment).as_str();
+    }
+    query += ";";
+    Ok(query)
+}
+
+/// Build the `INSERT VERTEX` statement of the vertices with the same properties
+/// 构造属性相同的多个点的 `INSERT VERTEX` 语句
+pub fn insert_vertex_statement(tag_name: &str, property_names: &[&str], rows: &[VertexRow]) -> Result<String> {
+    let literals = rows.iter().map(VertexRow::literal).collect::<Result<Vec<String>>>()?;
+    Ok(insert_vertex_header(tag_name, property_names, false)? + literals.join(", ").as_str() + ";")
+}
+
+/// Build the `INSERT EDGE` statement of the edges with the same properties
+/// 构造属性相同的多条边的 `INSERT EDGE` 语句
+pub fn insert_edge_statement(edge_name: &str, property_names: &[&str], rows: &[EdgeRow]) -> Result<String> {
+    let literals = rows.iter().map(EdgeRow::literal).collect::<Result<Vec<String>>>()?;
+    Ok(insert_edge_header(edge_name, property_names, false)? + literals.join(", ").as_str() + ";")
+}
+
+fn insert_vertex_header(tag_name: &str, property_names: &[&str], if_not_exists: bool) -> Result<String> {
+    let mut query = String::from("INSERT VERTEX ");
+    if if_not_exists {
+        query += "IF NOT EXISTS ";
+    }
+    query += escape::quote_identifier(tag_name)?.as_str();
+    query += property_list(property_names)?.as_str();
+    query += " VALUES ";
+    Ok(query)
+}
+
+fn insert_edge_header(edge_name: &str, property_names: &[&str], if_not_exists: bool) -> Result<String> {
+    let mut query = String::from("INSERT EDGE ");
+    if if_not_exists {
+        query += "IF NOT EXISTS ";
+    }
+    query += escape::quote_identifier(edge_name)?.as_str();
+    query += property_list(property_names)?.as_str();
+    query += " VALUES ";
+    Ok(query)
+}
+
+/// Size limits of one batched `INSERT` or `DELETE` statement
+/// 批量 `INSERT` 或 `DELETE` 语句的大小限制
+#[derive(Debug, Clone)]
+pub struct BatchLimit {
+    /// max count of rows in one statement, 0 means no limit
+    pub max_rows: usize,
+    /// max bytes of one statement, 0 means no limit, a larger row is sent alone
+    pub max_bytes: usize,
+}
+
+impl Default for BatchLimit {
+    fn default() -> Self {
+        BatchLimit {
+            max_rows: 500,
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl BatchLimit {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn max_rows(&mut self, max_rows: usize) -> &mut Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    #[inline]
+    pub fn max_bytes(&mut self, max_bytes: usize) -> &mut Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+}
+
+/// One batched `INSERT` or `DELETE` statement, which runs in the space
+/// 一条批量 `INSERT` 或 `DELETE` 语句，在指定图空间中执行
+#[derive(Debug, Clone)]
+pub struct StatementBatch {
+    pub space_name: String,
+    pub statement: String,
+    /// count of rows in the statement
+    pub rows: usize,
+}
+
+/// The result of sending one batch
+/// 发送一个批次的结果
+#[derive(Debug)]
+pub struct BatchResult {
+    pub batch: StatementBatch,
+    pub result: crate::Result<ResultSet>,
+}
+
+/// Group the vertices by space, tag and names of properties into batched `INSERT VERTEX` statements,
+/// the groups keep the order of their first vertices. With `if_not_exists` the existing vertices are
+/// kept as `insert_tag` does, otherwise they are overwritten
+/// 按图空间、标签和属性名将点分组，构造批量 `INSERT VERTEX` 语句，各组保持其首个点的顺序，
+/// `if_not_exists` 为真时与 `insert_tag` 一样保留已存在的点，否则覆盖
+pub fn batch_insert_tags(queries: &[InsertTagQuery], if_not_exists: bool, limit: &BatchLimit) -> Result<Vec<StatementBatch>> {
+    let groups = group_by(queries, |query| (&query.space_name, &query.tag_name, &query.kv));
+    let mut batches = Vec::new();
+    for ((space_name, tag_name, names), queries) in groups {
+        let rows = queries
+            .into_iter()
+            .map(|query| {
+                VertexRow {
+                    vid: Value::from(query.vid.as_str()),
+                    values: names.iter().map(|name| query.kv[*name].clone()).collect(),
+                }
+                .literal()
+            })
+            .collect::<Result<Vec<String>>>()?;
+        let header = insert_vertex_header(tag_name, &names, if_not_exists)?;
+        pack(space_name, &header, "", rows, limit, &mut batches);
+    }
+    Ok(batches)
+}
+
+/// Group the edges by space, edge type and names of properties into batched `INSERT EDGE` statements,
+/// the groups keep the order of their first edges. With `if_not_exists` the existing edges are
+/// kept as `insert_edge` does, otherwise they are overwritten
+/// 按图空间、边类型和属性名将边分组，构造批量 `INSERT EDGE` 语句，各组保持其首条边的顺序，
+/// `if_not_exists` 为真时与 `insert_edge` 一样保留已存在的边，否则覆盖
+pub fn batch_insert_edges(queries: &[InsertEdgeQueryWithRank], if_not_exists: bool, limit: &BatchLimit) -> Result<Vec<StatementBatch>> {
+    let groups = group_by(queries, |query| (&query.space_name, &query.edge_name, &query.kv));
+    let mut batches = Vec::new();
+    for ((space_name, edge_name, names), queries) in groups {
+        let rows = queries
+            .into_iter()
+            .map(|query| {
+                EdgeRow {
+                    src: Value::from(query.from_vertex.as_str()),
+                    dst: Value::from(query.to_vertex.as_str()),
+                    rank: query.rank,
+                    values: names.iter().map(|name| query.kv[*name].clone()).collect(),
+                }
+                .literal()
+            })
+            .collect::<Result<Vec<String>>>()?;
+        let header = insert_edge_header(edge_name, &names, if_not_exists)?;
+        pack(space_name, &header, "", rows, limit, &mut batches);
+    }
+    Ok(batches)
+}
+
+type GroupKey<'a> = (&'a str, &'a str, Vec<&'a str>);
+
+/// Group the queries by space, schema and sorted names of properties
+fn group_by<'a, Q, F>(queries: &'a [Q], key: F) -> Vec<(GroupKey<'a>, Vec<&'a Q>)>
+where
+    F: Fn(&'a Q) -> (&'a String, &'a String, &'a HashMap<String, Value>),
+{
+    let mut groups: Vec<(GroupKey<'a>, Vec<&'a Q>)> = Vec::new();
+    let mut indexes: HashMap<GroupKey<'a>, usize> = HashMap::new();
+    for query in queries {
+        let (space_name, name, kv) = key(query);
+        let mut names: Vec<&str> = kv.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        let group_key = (space_name.as_str(), name.as_str(), names);
+        match indexes.get(&group_key) {
+            Some(&index) => groups[index].1.push(query),
+            None => {
+                indexes.insert(group_key.clone(), groups.len());
+                groups.push((group_key, vec![query]));
+            }
+        }
+    }
+    groups
+}
+
+/// Pack the rows into statements `header row, row.. footer;` within the limit
+fn pack(space_name: &str, header: &str, footer: &str, rows: Vec<String>, limit: &BatchLimit, batches: &mut Vec<StatementBatch>) {
+    let mut statement = String::from(header);
+    let mut count = 0;
+    for row in rows {
+        let full_rows = limit.max_rows != 0 && count >= limit.max_rows;
+        // ", " before the row and ";" at the end
+        let full_bytes =
+            limit.max_bytes != 0 && statement.len() + row.len() + footer.len() + 3 > limit.max_bytes;
+        if count != 0 && (full_rows || full_bytes) {
+            statement += footer;
+            statement += ";";
+            batches.push(StatementBatch {
+                space_name: space_name.to_string(),
+                statement: std::mem::replace(&mut statement, String::from(header)),
+                rows: count,
+            });
+            count = 0;
+        }
+        if count != 0 {
+            statement += ", ";
+        }
+        statement += row.as_str();
+        count += 1;
+    }
+    if count != 0 {
+        statement += footer;
+        statement += ";";
+        batches.push(StatementBatch {
+            space_name: space_name.to_string(),
+            statement,
+            rows: count,
+        });
+    }
+}
+
+fn property_list(property_names: &[&str]) -> Result<String> {
+    let names = property_names
+        .iter()
+        .map(|name| escape::quote_identifier(name))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(format!("({})", names.join(", ")))
+}
+
+fn value_list(values: &[Value]) -> Result<String> {
+    Ok(format!("({})", literals(values)?))
+}
+
+/// Render the value as nGQL literal, e.g. `"Tom"`, `date("2021-01-02")`, `[1, 2]` or `{a: 1}`.
+/// Fail on the values which have no nGQL literal: NaN and infinite floats, `i64::MIN`,
+/// an empty set, a map key which can't be quoted, vertex, edge, path and data set
+/// 将值渲染为 nGQL 字面量，没有对应字面量的值（NaN 与无穷浮点数、`i64::MIN`、空集合、
+/// 无法引用的映射键、点、边、路径、数据集）返回错误
+pub fn value_literal(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::nVal(_) => String::from("NULL"),
+        Value::bVal(b) => b.to_string(),
+        // the lexer reads the digits before the minus sign, 9223372036854775808 overflows
+        Value::iVal(i) if *i == i64::MIN => return Err(no_literal(&i.to_string())),
+        Value::iVal(i) => i.to_string(),
+        Value::fVal(f) if !f.0.is_finite() => return Err(no_literal(&f.0.to_string())),
+        // Debug keeps the fraction, so the literal is still a float
+        Value::fVal(f) => format!("{:?}", f.0),
+        Value::sVal(s) => escape::quote_string(&String::from_utf8_lossy(s)),
+        Value::dVal(d) => format!("date(\"{:04}-{:02}-{:02}\")", d.year, d.month, d.day),
+        Value::tVal(t) => format!(
+            "time(\"{:02}:{:02}:{:02}.{:06}\")",
+            t.hour, t.minute, t.sec, t.microsec
+        ),
+        Value::dtVal(dt) => format!(
+            "datetime(\"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}\")",
+            dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.sec, dt.microsec
+        ),
+        Value::lVal(list) => format!("[{}]", literals(&list.values)?),
+        Value::mVal(map) => {
+            let kvs = map
+                .kvs
+                .iter()
+                .map(|(k, v)| {
+                    Ok(format!(
+                        "{}: {}",
+                        escape::quote_identifier(&String::from_utf8_lossy(k))?,
+                        value_literal(v)?
+                    ))
+                })
+                .collect::<Result<Vec<String>>>()?;
+            format!("{{{}}}", kvs.join(", "))
+        }
+        // `{}` is an empty map
+        Value::uVal(set) if set.values.is_empty() => return Err(no_literal("an empty set")),
+        Value::uVal(set) => format!("{{{}}}", literals(&set.values)?),
+        Value::vVal(_) => return Err(no_literal("a vertex")),
+        Value::eVal(_) => return Err(no_literal("an edge")),
+        Value::pVal(_) => return Err(no_literal("a path")),
+        Value::gVal(_) => return Err(no_literal("a data set")),
+        Value::UnknownField(_) => return Err(no_literal("an unknown value")),
+    })
+}
+
+fn no_literal(what: &str) -> Error {
+    Error::Statement(format!("{} has no nGQL literal", what))
 }
 
+/// Literals of the values separated by `, `
+fn literals<'a, I: IntoIterator<Item = &'a Value>>(values: I) -> Result<String> {
+    let values = values.into_iter().map(value_literal).collect::<Result<Vec<String>>>()?;
+    Ok(values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_literal() {
+        assert_eq!(value_literal(&Value::from(1i64)).unwrap(), "1");
+        assert_eq!(value_literal(&Value::from(-1i64)).unwrap(), "-1");
+        assert_eq!(value_literal(&Value::from(i64::MAX)).unwrap(), i64::MAX.to_string());
+        assert_eq!(value_literal(&Value::from(1.0f64)).unwrap(), "1.0");
+        assert_eq!(value_literal(&Value::from(true)).unwrap(), "true");
+        assert_eq!(value_literal(&Value::from("a\"b")).unwrap(), "\"a\\\"b\"");
+        assert_eq!(value_literal(&Value::nVal(common::types::NullType::__NULL__)).unwrap(), "NULL");
+
+        let list = Value::lVal(Box::new(common::types::NList {
+            values: vec![Value::from(1i64), Value::from("a")],
+        }));
+        assert_eq!(value_literal(&list).unwrap(), "[1, \"a\"]");
+        let mut kvs = std::collections::BTreeMap::new();
+        kvs.insert(b"k".to_vec(), Value::from(1i64));
+        let map = Value::mVal(Box::new(common::types::NMap { kvs }));
+        assert_eq!(value_literal(&map).unwrap(), "{`k`: 1}");
+        let mut values = std::collections::BTreeSet::new();
+        values.insert(Value::from(1i64));
+        let set = Value::uVal(Box::new(common::types::NSet { values }));
+        assert_eq!(value_literal(&set).unwrap(), "{1}");
+    }
+
+    #[test]
+    fn test_value_literal_error() {
+        let invalid = vec![
+            Value::from(f64::NAN),
+            Value::from(f64::INFINITY),
+            Value::from(f64::NEG_INFINITY),
+            Value::from(i64::MIN),
+            Value::uVal(Box::new(common::types::NSet {
+                values: std::collections::BTreeSet::new(),
+            })),
+            Value::vVal(Box::new(common::types::Vertex {
+                vid: Value::from("v"),
+                tags: Vec::new(),
+            })),
+            Value::UnknownField(0),
+        ];
+        for value in invalid {
+            assert!(
+                matches!(value_literal(&value), Err(Error::Statement(_))),
+                "{:?}",
+                value
+            );
+        }
+        let list = Value::lVal(Box::new(common::types::NList {
+            values: vec![Value::from(f64::NAN)],
+        }));
+        assert!(matches!(value_literal(&list), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_insert_tag_query() {
+        let mut kv = HashMap::new();
+        kv.insert(String::from("name"), Value::from("Tom"));
+        let query = InsertTagQuery::new(String::from("s"), String::from("t"), kv, String::from("v1"));
+        assert_eq!(
+            query.to_string().unwrap(),
+            "USE `s`; INSERT VERTEX `t` (`name`) VALUES \"v1\":(\"Tom\");"
+        );
+
+        let mut kv = HashMap::new();
+        kv.insert(String::from("w"), Value::from(f64::NAN));
+        let query = InsertTagQuery::new(String::from("s"), String::from("t"), kv, String::from("v1"));
+        assert!(matches!(query.to_string(), Err(Error::Statement(_))));
+    }
+
+    fn tag_query(space_name: &str, tag_name: &str, vid: &str, kv: &[(&str, i64)]) -> InsertTagQuery {
+        let kv = kv.iter().map(|(k, v)| (k.to_string(), Value::from(*v))).collect();
+        InsertTagQuery::new(space_name.to_string(), tag_name.to_string(), kv, vid.to_string())
+    }
+
+    #[test]
+    fn test_group_by() {
+        let queries = vec![
+            tag_query("s", "t", "1", &[("a", 1), ("b", 2)]),
+            tag_query("s", "u", "2", &[("a", 1)]),
+            tag_query("s", "t", "3", &[("b", 3), ("a", 4)]),
+            tag_query("s", "t", "4", &[("a", 5)]),
+            tag_query("s2", "t", "5", &[("a", 6), ("b", 7)]),
+        ];
+        let groups = group_by(&queries, |query| (&query.space_name, &query.tag_name, &query.kv));
+        let groups: Vec<(GroupKey, Vec<&str>)> = groups
+            .into_iter()
+            .map(|(key, queries)| (key, queries.iter().map(|query| query.vid.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (("s", "t", vec!["a", "b"]), vec!["1", "3"]),
+                (("s", "u", vec!["a"]), vec!["2"]),
+                (("s", "t", vec!["a"]), vec!["4"]),
+                (("s2", "t", vec!["a", "b"]), vec!["5"]),
+            ]
+        );
+    }
+
+    fn rows(rows: &[&str]) -> Vec<String> {
+        rows.iter().map(|row| row.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pack() {
+        let mut batches = Vec::new();
+        pack("s", "H ", " F", rows(&["1", "2", "3"]), BatchLimit::new().max_rows(2), &mut batches);
+        let statements: Vec<(&str, usize)> = batches.iter().map(|b| (b.statement.as_str(), b.rows)).collect();
+        assert_eq!(statements, vec![("H 1, 2 F;", 2), ("H 3 F;", 1)]);
+        assert!(batches.iter().all(|b| b.space_name == "s"));
+
+        // "H 1, 2;" is 7 bytes
+        let mut batches = Vec::new();
+        let limit = BatchLimit::new().max_rows(0).max_bytes(7).clone();
+        pack("s", "H ", "", rows(&["1", "2", "3"]), &limit, &mut batches);
+        let statements: Vec<&str> = batches.iter().map(|b| b.statement.as_str()).collect();
+        assert_eq!(statements, vec!["H 1, 2;", "H 3;"]);
+
+        // a row larger than the limit is sent alone
+        let mut batches = Vec::new();
+        let limit = BatchLimit::new().max_bytes(5).clone();
+        pack("s", "H ", "", rows(&["1", "long", "2"]), &limit, &mut batches);
+        let statements: Vec<&str> = batches.iter().map(|b| b.statement.as_str()).collect();
+        assert_eq!(statements, vec!["H 1;", "H long;", "H 2;"]);
+
+        let mut batches = Vec::new();
+        pack("s", "H ", "", Vec::new(), &BatchLimit::default(), &mut batches);
+        assert!(batches.is_empty());
+    }
+
+    #[test]
+    fn test_batch_insert_tags() {
+        let queries = vec![
+            tag_query("s", "t", "1", &[("a", 1)]),
+            tag_query("s", "t", "2", &[("a", 2)]),
+            tag_query("s", "t", "3", &[("b", 3)]),
+        ];
+        let batches = batch_insert_tags(&queries, true, &BatchLimit::default()).unwrap();
+        let statements: Vec<&str> = batches.iter().map(|b| b.statement.as_str()).collect();
+        assert_eq!(
+            statements,
+            vec![
+                "INSERT VERTEX IF NOT EXISTS `t`(`a`) VALUES \"1\":(1), \"2\":(2);",
+                "INSERT VERTEX IF NOT EXISTS `t`(`b`) VALUES \"3\":(3);",
+            ]
+        );
+
+        let batches = batch_insert_tags(&queries[..2], false, BatchLimit::new().max_rows(1)).unwrap();
+        let statements: Vec<&str> = batches.iter().map(|b| b.statement.as_str()).collect();
+        assert_eq!(
+            statements,
+            vec![
+                "INSERT VERTEX `t`(`a`) VALUES \"1\":(1);",
+                "INSERT VERTEX `t`(`a`) VALUES \"2\":(2);",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_insert_edges() {
+        let mut kv = HashMap::new();
+        kv.insert(String::from("w"), Value::from(1i64));
+        let query = |src: &str, rank| {
+            InsertEdgeQueryWithRank::new(String::from("s"), String::from("e"), kv.clone(), src.to_string(), String::from("d"), rank)
+        };
+        let queries = vec![query("a", 0), query("b", 1)];
+        let batches = batch_insert_edges(&queries, true, &BatchLimit::default()).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].rows, 2);
+        assert_eq!(
+            batches[0].statement,
+            "INSERT EDGE IF NOT EXISTS `e`(`w`) VALUES \"a\" -> \"d\"@0:(1), \"b\" -> \"d\"@1:(1);"
+        );
+
+        let batches = batch_insert_edges(&queries, false, &BatchLimit::default()).unwrap();
+        assert!(batches[0].statement.starts_with("INSERT EDGE `e`(`w`) VALUES "));
+    }
+
+    fn server_error(code: common::types::ErrorCode, message: &str) -> Error {
+        Error::Server {
+            code,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_schema_missing() {
+        use common::types::ErrorCode;
+        assert!(server_error(ErrorCode::E_TAG_NOT_FOUND, "").is_schema_missing());
+        assert!(server_error(ErrorCode::E_EDGE_NOT_FOUND, "").is_schema_missing());
+        assert!(server_error(ErrorCode::E_SEMANTIC_ERROR, "No schema found for `t'").is_schema_missing());
+        assert!(!server_error(ErrorCode::E_SEMANTIC_ERROR, "Wrong number of value").is_schema_missing());
+        // A misspelled property or a missing vertex is the mistake of user
+        assert!(!server_error(ErrorCode::E_SEMANTIC_ERROR, "`nmae' not found in tag `t'").is_schema_missing());
+        assert!(!server_error(ErrorCode::E_EXECUTION_ERROR, "Storage Error: Vertex or edge not found.").is_schema_missing());
+        assert!(!server_error(ErrorCode::E_SYNTAX_ERROR, "not found").is_schema_missing());
+        assert!(!Error::Timeout(String::from("not found")).is_schema_missing());
+    }
+
+    #[tokio::test]
+    async fn test_retry_while_schema_missing() {
+        let calls = std::cell::Cell::new(0);
+        let result = retry_while_schema_missing(10_000, || {
+            calls.set(calls.get() + 1);
+            let result = if calls.get() < 3 {
+                Err(server_error(common::types::ErrorCode::E_TAG_NOT_FOUND, ""))
+            } else {
+                Ok(calls.get())
+            };
+            async move { result }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let calls = std::cell::Cell::new(0);
+        let result: Result<()> = retry_while_schema_missing(10_000, || {
+            calls.set(calls.get() + 1);
+            async { Err(server_error(common::types::ErrorCode::E_SYNTAX_ERROR, "")) }
+        })
+        .await;
+        assert!(matches!(result, Err(Error::Server { .. })));
+        assert_eq!(calls.get(), 1);
+
+        let result: Result<()> = retry_while_schema_missing(0, || async {
+            Err(server_error(common::types::ErrorCode::E_EDGE_NOT_FOUND, ""))
+        })
+        .await;
+        assert!(result.unwrap_err().is_schema_missing());
+    }
+
+    fn describe_space(vid_type: &str) -> ResultSet {
+        ResultSet::new(graph::types::ExecutionResponse {
+            error_code: common::types::ErrorCode::SUCCEEDED,
+            latency_in_us: 0,
+            data: Some(common::types::DataSet {
+                column_names: vec![b"Name".to_vec(), b"Vid Type".to_vec()],
+                rows: vec![common::types::Row {
+                    values: vec![Value::from("s"), Value::from(vid_type)],
+                }],
+            }),
+            space_name: None,
+            error_msg: None,
+            plan_desc: None,
+            comment: None,
+        })
+    }
+
+    /// Run `wait_for_schema` against the fake server, the probe fails `missing` times
+    async fn statements_of_wait(col_type: ColType, vid_type: &str, missing: usize, timeout: u32) -> (Result<()>, Vec<String>) {
+        let statements = std::cell::RefCell::new(Vec::new());
+        let result = wait_for_schema("s", "t", col_type, timeout, |query| {
+            let result = if query.starts_with("DESCRIBE") {
+                Ok(describe_space(vid_type))
+            } else if statements.borrow().len() <= missing {
+                Err(server_error(common::types::ErrorCode::E_SEMANTIC_ERROR, "No schema found for `t'"))
+            } else {
+                Ok(describe_space(vid_type))
+            };
+            statements.borrow_mut().push(query);
+            async move { result }
+        })
+        .await;
+        (result, statements.into_inner())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_schema() {
+        let (result, statements) = statements_of_wait(ColType::Tag, "INT64", 1, 10_000).await;
+        result.unwrap();
+        assert_eq!(
+            statements,
+            vec![
+                "DESCRIBE SPACE `s`;",
+                "FETCH PROP ON `t` 0 YIELD vertex AS `v`;",
+                "FETCH PROP ON `t` 0 YIELD vertex AS `v`;",
+            ]
+        );
+
+        let (result, statements) = statements_of_wait(ColType::Edge, "FIXED_STRING(32)", 0, 10_000).await;
+        result.unwrap();
+        assert_eq!(
+            statements,
+            vec!["DESCRIBE SPACE `s`;", "FETCH PROP ON `t` \"0\" -> \"0\"@0 YIELD edge AS `e`;"]
+        );
+
+        let (result, _) = statements_of_wait(ColType::Tag, "INT64", usize::MAX, 0).await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    fn statements(batches: &[StatementBatch]) -> Vec<(&str, usize)> {
+        batches.iter().map(|b| (b.statement.as_str(), b.rows)).collect()
+    }
+
+    #[test]
+    fn test_update_query() {
+        let mut query = UpdateQuery::vertex("s", "t", "v1");
+        query
+            .set("name", "Tom")
+            .set_expr("age", "age + 1")
+            .when("age > 18")
+            .yield_prop("name")
+            .yield_prop_as("age", "a");
+        assert_eq!(
+            query.statement().unwrap(),
+            "UPDATE VERTEX ON `t` \"v1\" SET `name` = \"Tom\", `age` = age + 1 WHEN age > 18 YIELD `name`, `age` AS `a`;"
+        );
+
+        let mut query = UpdateQuery::edge("s", "e", 1i64, 2i64, 3);
+        query.upsert(true).set("w", 1.5f64);
+        assert_eq!(query.statement().unwrap(), "UPSERT EDGE ON `e` 1 -> 2@3 SET `w` = 1.5;");
+
+        let query = UpdateQuery::vertex("s", "t", "v1");
+        assert!(matches!(query.statement(), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_delete_vertex_query() {
+        let mut query = DeleteVertexQuery::new("s", true);
+        query.vid("a").vid("b").vid("c");
+        let batches = query.batches(&BatchLimit::default()).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![("DELETE VERTEX \"a\", \"b\", \"c\" WITH EDGE;", 3)]
+        );
+        assert_eq!(batches[0].space_name, "s");
+
+        // "DELETE VERTEX \"a\", \"b\" WITH EDGE;" is 33 bytes
+        let batches = query.batches(BatchLimit::new().max_bytes(33)).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![
+                ("DELETE VERTEX \"a\", \"b\" WITH EDGE;", 2),
+                ("DELETE VERTEX \"c\" WITH EDGE;", 1),
+            ]
+        );
+
+        let mut query = DeleteVertexQuery::new("s", false);
+        query.vid(1i64).vid(2i64);
+        let batches = query.batches(BatchLimit::new().max_rows(1)).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![("DELETE VERTEX 1;", 1), ("DELETE VERTEX 2;", 1)]
+        );
+        assert!(DeleteVertexQuery::new("s", false).batches(&BatchLimit::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_edge_query() {
+        let mut query = DeleteEdgeQuery::new("s", "e");
+        query.edge("a", "b", 0).edge("a", "c", 1).edge("b", "c", 2);
+        let batches = query.batches(&BatchLimit::default()).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![("DELETE EDGE `e` \"a\" -> \"b\"@0, \"a\" -> \"c\"@1, \"b\" -> \"c\"@2;", 3)]
+        );
+
+        // "DELETE EDGE `e` \"a\" -> \"b\"@0, \"a\" -> \"c\"@1;" is 43 bytes
+        let batches = query.batches(BatchLimit::new().max_bytes(43)).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![
+                ("DELETE EDGE `e` \"a\" -> \"b\"@0, \"a\" -> \"c\"@1;", 2),
+                ("DELETE EDGE `e` \"b\" -> \"c\"@2;", 1),
+            ]
+        );
+        assert!(matches!(
+            DeleteEdgeQuery::new("s", "a`b").edge("a", "b", 0).batches(&BatchLimit::default()),
+            Err(Error::Statement(_))
+        ));
+    }
+
+    #[test]
+    fn test_delete_tag_query() {
+        let mut query = DeleteTagQuery::new("s", &["t1", "t2"]);
+        query.vid("a").vid("b");
+        let batches = query.batches(&BatchLimit::default()).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![("DELETE TAG `t1`, `t2` FROM \"a\", \"b\";", 2)]
+        );
+
+        let mut query = DeleteTagQuery::new("s", &[]);
+        query.vid("a").vid("b");
+        // "DELETE TAG * FROM \"a\";" is 22 bytes, "\"b\"" doesn't fit
+        let batches = query.batches(BatchLimit::new().max_bytes(26)).unwrap();
+        assert_eq!(
+            statements(&batches),
+            vec![("DELETE TAG * FROM \"a\";", 1), ("DELETE TAG * FROM \"b\";", 1)]
+        );
+    }
+
+    #[test]
+    fn test_is_call() {
+        assert!(is_call("now()"));
+        assert!(is_call("_f1()"));
+        assert!(!is_call("now"));
+        assert!(!is_call("()"));
+        assert!(!is_call("1f()"));
+        assert!(!is_call("now(1)"));
+        assert!(!is_call("a.b()"));
+        assert!(!is_call("x\"); DROP SPACE s; now()"));
+    }
+
+    #[test]
+    fn test_default_literal() {
+        let cases = vec![
+            (DataType::Int, "18", "18"),
+            (DataType::Double, "1.5", "1.5"),
+            (DataType::Bool, "TRUE", "true"),
+            (DataType::String, "now()", "\"now()\""),
+            (DataType::FixedString(4), "a\"b", "\"a\\\"b\""),
+            (DataType::Date, "2021-01-02", "date(\"2021-01-02\")"),
+            (DataType::Time, "12:00:00", "time(\"12:00:00\")"),
+            (DataType::DateTime, "2021-01-02T12:00:00", "datetime(\"2021-01-02T12:00:00\")"),
+            (DataType::Timestamp, "now()", "now()"),
+            (DataType::Timestamp, "1625469277", "1625469277"),
+            (DataType::Timestamp, "2021-07-05T06:18:43", "timestamp(\"2021-07-05T06:18:43\")"),
+            (DataType::Duration, "{days: 1, Hours: -2}", "duration({days: 1, hours: -2})"),
+            (DataType::Geography(GeoShape::Point), "POINT(1 2)", "ST_GeogFromText(\"POINT(1 2)\")"),
+        ];
+        for (data_type, defaults, literal) in cases {
+            assert_eq!(data_type.default_literal(defaults).unwrap(), literal, "{}", defaults);
+        }
+        for defaults in ["P1D", "{}", "{days: 1.5}", "{weeks: 1}", "{days 1}", "days: 1"] {
+            assert!(
+                matches!(DataType::Duration.default_literal(defaults), Err(Error::Statement(_))),
+                "{}",
+                defaults
+            );
+        }
+        let invalid = vec![
+            (DataType::Int, "abc"),
+            (DataType::Int8, "1.5"),
+            (DataType::Int32, ""),
+            (DataType::Double, "abc"),
+            (DataType::Float, "NaN"),
+            (DataType::Bool, "yes"),
+            (DataType::Timestamp, "abc"),
+            (DataType::Timestamp, "1.5e3"),
+        ];
+        for (data_type, defaults) in invalid {
+            assert!(
+                matches!(data_type.default_literal(defaults), Err(Error::Statement(_))),
+                "{}",
+                defaults
+            );
+        }
+    }
+
+    #[test]
+    fn test_create_schema_statement() {
+        let properties = [
+            Tag::new("name", DataType::String, false, "", "the name"),
+            Tag::new("created", DataType::Timestamp, true, "now()", ""),
+        ];
+        assert_eq!(
+            create_schema_statement(ColType::Tag, "t", "", &properties, None).unwrap(),
+            "CREATE tag IF NOT EXISTS `t` (`name` string NOT NULL COMMENT \"the name\" ,\
+             `created` timestamp NULL DEFAULT now() );"
+        );
+        let ttl = Ttl::new(100, "created");
+        assert_eq!(
+            create_schema_statement(ColType::Edge, "e", "c", &properties[1..], Some(&ttl)).unwrap(),
+            "CREATE edge IF NOT EXISTS `e` (`created` timestamp NULL DEFAULT now() ) \
+             TTL_DURATION = 100, TTL_COL = \"created\" COMMENT = \"c\";"
+        );
+        let properties = [Tag::new("d", DataType::Duration, true, "1 day", "")];
+        assert!(matches!(
+            create_schema_statement(ColType::Tag, "t", "", &properties, None),
+            Err(Error::Statement(_))
+        ));
+    }
+}
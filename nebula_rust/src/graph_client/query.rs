@@ -0,0 +1,884 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use common::types::Value;
+
+use crate::error::{Error, Result};
+use crate::graph_client::escape;
+use crate::graph_client::nebula_schema::value_literal;
+
+/// Property of the vertex or edge, e.g. `prop(&["follow", "degree"])` is `` `follow`.`degree` ``
+/// 点或边的属性，例如 `prop(&["follow", "degree"])` 为 `` `follow`.`degree` ``
+pub fn prop(path: &[&str]) -> Result<String> {
+    let names = path
+        .iter()
+        .map(|name| escape::quote_identifier(name))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(names.join("."))
+}
+
+/// Property of the source vertex in `GO`, `$^.tag.prop`
+/// `GO` 中起始点的属性
+pub fn src_prop(tag_name: &str, property: &str) -> Result<String> {
+    Ok(format!("$^.{}", prop(&[tag_name, property])?))
+}
+
+/// Property of the destination vertex in `GO`, `$$.tag.prop`
+/// `GO` 中目的点的属性
+pub fn dst_prop(tag_name: &str, property: &str) -> Result<String> {
+    Ok(format!("$$.{}", prop(&[tag_name, property])?))
+}
+
+/// Column of the output before the pipe, `$-.column`
+/// 管道前语句输出的列
+pub fn input(column: &str) -> Result<String> {
+    Ok(format!("$-.{}", escape::quote_identifier(column)?))
+}
+
+/// Column of the output assigned to the variable, `$var.column`
+/// 赋值给变量的输出中的列
+pub fn var(name: &str, column: &str) -> Result<String> {
+    Ok(format!("{}.{}", escape::variable(name)?, escape::quote_identifier(column)?))
+}
+
+/// nGQL literal of the value
+/// 值的 nGQL 字面量
+#[inline]
+pub fn literal<V: Into<Value>>(value: V) -> Result<String> {
+    value_literal(&value.into())
+}
+
+/// The sentence which could be connected by pipes or assigned to variable,
+/// the raw nGQL in `&str` or `String` is not escaped
+/// 可以用管道连接或赋值给变量的语句，`&str` 或 `String` 中的 nGQL 不会被转义
+pub trait Sentence {
+    /// Build the sentence without `;`
+    fn sentence(&self) -> Result<String>;
+
+    /// The statement ended with `;`
+    fn statement(&self) -> Result<String> {
+        Ok(self.sentence()? + ";")
+    }
+}
+
+impl Sentence for &str {
+    #[inline]
+    fn sentence(&self) -> Result<String> {
+        Ok(self.to_string())
+    }
+}
+
+impl Sentence for String {
+    #[inline]
+    fn sentence(&self) -> Result<String> {
+        Ok(self.clone())
+    }
+}
+
+/// Connect the sentences by pipes, e.g. `GO .. | FETCH ..;`
+/// 用管道连接多个语句
+pub fn pipe(sentences: &[&dyn Sentence]) -> Result<String> {
+    let sentences = sentences
+        .iter()
+        .map(|sentence| sentence.sentence())
+        .collect::<Result<Vec<String>>>()?;
+    Ok(sentences.join(" | ") + ";")
+}
+
+/// Assign the output of sentence to the variable, e.g. `$var = GO ..;`
+/// 将语句的输出赋值给变量
+pub fn assign(name: &str, sentence: &dyn Sentence) -> Result<String> {
+    Ok(format!("{} = {};", escape::variable(name)?, sentence.sentence()?))
+}
+
+/// The start vertices
+/// 起始点
+#[derive(Debug, Clone)]
+pub enum Source {
+    Vids(Vec<Value>),
+    /// `$-.column`
+    Input(String),
+    /// `$var.column`
+    Var(String, String),
+}
+
+impl Source {
+    pub fn vids<V: Into<Value>, I: IntoIterator<Item = V>>(vids: I) -> Self {
+        Source::Vids(vids.into_iter().map(Into::into).collect())
+    }
+
+    pub fn input(column: &str) -> Self {
+        Source::Input(column.to_string())
+    }
+
+    pub fn var(name: &str, column: &str) -> Self {
+        Source::Var(name.to_string(), column.to_string())
+    }
+
+    fn render(&self) -> Result<String> {
+        match self {
+            Source::Vids(vids) => {
+                let vids = vids.iter().map(value_literal).collect::<Result<Vec<String>>>()?;
+                Ok(vids.join(", "))
+            }
+            Source::Input(column) => input(column),
+            Source::Var(name, column) => var(name, column),
+        }
+    }
+
+    /// The column referred by one end or the rank of edges
+    fn reference(&self) -> Result<String> {
+        match self {
+            Source::Vids(_) => Err(Error::Statement(String::from(
+                "the edge refers to the column of input or variable, add the vids by `edge`",
+            ))),
+            _ => self.render(),
+        }
+    }
+}
+
+/// Direction of edges to traverse
+/// 遍历边的方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// Outgoing edges
+    Out,
+    /// Incoming edges
+    In,
+    /// Both directions
+    Both,
+}
+
+/// Expressions with optional aliases in `YIELD` or `RETURN`
+#[derive(Debug, Clone, Default)]
+struct Columns(Vec<(String, Option<String>)>);
+
+impl Columns {
+    fn push(&mut self, expr: &str, alias: Option<&str>) {
+        self.0.push((expr.to_string(), alias.map(str::to_string)));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn render(&self) -> Result<String> {
+        let columns = self
+            .0
+            .iter()
+            .map(|(expr, alias)| match alias {
+                Some(alias) => Ok(format!("{} AS {}", expr, escape::quote_identifier(alias)?)),
+                None => Ok(expr.clone()),
+            })
+            .collect::<Result<Vec<String>>>()?;
+        Ok(columns.join(", "))
+    }
+}
+
+/// The quoted names separated by `sep`, `*` if empty
+fn names(names: &[String], sep: &str) -> Result<String> {
+    if names.is_empty() {
+        return Ok(String::from("*"));
+    }
+    let names = names
+        .iter()
+        .map(|name| escape::quote_identifier(name))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(names.join(sep))
+}
+
+/// `GO [m TO] n STEPS FROM .. OVER .. [REVERSELY | BIDIRECT] [WHERE ..] YIELD ..`,
+/// the expressions in `WHERE` and `YIELD` are nGQL, build them by `prop`, `input`, `literal` ...
+/// `GO` 语句，`WHERE` 和 `YIELD` 中的表达式为 nGQL，可通过 `prop`、`input`、`literal` 等构造
+#[derive(Debug, Clone)]
+pub struct Go {
+    from: Source,
+    min_steps: Option<u32>,
+    steps: Option<u32>,
+    edges: Vec<String>,
+    direction: Direction,
+    condition: Option<String>,
+    distinct: bool,
+    columns: Columns,
+}
+
+impl Go {
+    pub fn new(from: Source) -> Self {
+        Go {
+            from,
+            min_steps: None,
+            steps: None,
+            edges: Vec::new(),
+            direction: Direction::Out,
+            condition: None,
+            distinct: false,
+            columns: Columns::default(),
+        }
+    }
+
+    /// `GO n STEPS`
+    #[inline]
+    pub fn steps(&mut self, steps: u32) -> &mut Self {
+        self.min_steps = None;
+        self.steps = Some(steps);
+        self
+    }
+
+    /// `GO m TO n STEPS`
+    #[inline]
+    pub fn steps_range(&mut self, min_steps: u32, max_steps: u32) -> &mut Self {
+        self.min_steps = Some(min_steps);
+        self.steps = Some(max_steps);
+        self
+    }
+
+    /// Traverse the edge type, all edge types if never called
+    #[inline]
+    pub fn over(&mut self, edge_name: &str) -> &mut Self {
+        self.edges.push(edge_name.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    #[inline]
+    pub fn where_(&mut self, condition: &str) -> &mut Self {
+        self.condition = Some(condition.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn distinct(&mut self, distinct: bool) -> &mut Self {
+        self.distinct = distinct;
+        self
+    }
+
+    #[inline]
+    pub fn yield_(&mut self, expr: &str) -> &mut Self {
+        self.columns.push(expr, None);
+        self
+    }
+
+    #[inline]
+    pub fn yield_as(&mut self, expr: &str, alias: &str) -> &mut Self {
+        self.columns.push(expr, Some(alias));
+        self
+    }
+}
+
+impl Sentence for Go {
+    fn sentence(&self) -> Result<String> {
+        let mut sentence = String::from("GO ");
+        if let Some(steps) = self.steps {
+            if let Some(min_steps) = self.min_steps {
+                sentence += format!("{} TO ", min_steps).as_str();
+            }
+            sentence += format!("{} STEPS ", steps).as_str();
+        }
+        sentence += format!("FROM {} OVER {}", self.from.render()?, names(&self.edges, ", ")?).as_str();
+        match self.direction {
+            Direction::Out => {}
+            Direction::In => sentence += " REVERSELY",
+            Direction::Both => sentence += " BIDIRECT",
+        }
+        if let Some(condition) = &self.condition {
+            sentence += " WHERE ";
+            sentence += condition.as_str();
+        }
+        if !self.columns.is_empty() {
+            sentence += " YIELD ";
+            if self.distinct {
+                sentence += "DISTINCT ";
+            }
+            sentence += self.columns.render()?.as_str();
+        }
+        Ok(sentence)
+    }
+}
+
+/// What to fetch
+#[derive(Debug, Clone)]
+enum FetchTarget {
+    Vertices { tag_names: Vec<String>, from: Source },
+    Edges { edge_name: String },
+    EdgeRefs { edge_name: String, src: Source, dst: Source, rank: Option<Source> },
+}
+
+/// `FETCH PROP ON .. YIELD ..` of vertices or edges
+/// 获取点或边属性的 `FETCH` 语句
+#[derive(Debug, Clone)]
+pub struct Fetch {
+    target: FetchTarget,
+    /// The edges added by `edge`
+    keys: Vec<(Value, Value, i64)>,
+    columns: Columns,
+}
+
+impl Fetch {
+    /// Fetch the vertices with the tags, all tags if `tag_names` is empty
+    pub fn vertices(tag_names: &[&str], from: Source) -> Self {
+        Fetch {
+            target: FetchTarget::Vertices {
+                tag_names: tag_names.iter().map(|name| name.to_string()).collect(),
+                from,
+            },
+            keys: Vec::new(),
+            columns: Columns::default(),
+        }
+    }
+
+    /// Fetch the edges of the edge type, add them by `edge`
+    pub fn edges(edge_name: &str) -> Self {
+        Fetch {
+            target: FetchTarget::Edges {
+                edge_name: edge_name.to_string(),
+            },
+            keys: Vec::new(),
+            columns: Columns::default(),
+        }
+    }
+
+    /// Fetch the edges whose src, dst and rank are the columns before the pipe or of the variable,
+    /// e.g. `Fetch::edge_refs("follow", Source::input("s"), Source::input("d"), None)` is
+    /// `` FETCH PROP ON `follow` $-.`s` -> $-.`d` ``, the rank is 0 if `None`
+    pub fn edge_refs(edge_name: &str, src: Source, dst: Source, rank: Option<Source>) -> Self {
+        Fetch {
+            target: FetchTarget::EdgeRefs {
+                edge_name: edge_name.to_string(),
+                src,
+                dst,
+                rank,
+            },
+            keys: Vec::new(),
+            columns: Columns::default(),
+        }
+    }
+
+    /// Add the edge `src -> dst@rank`, building the sentence fails unless fetching by `edges`
+    #[inline]
+    pub fn edge<V: Into<Value>>(&mut self, src: V, dst: V, rank: i64) -> &mut Self {
+        self.keys.push((src.into(), dst.into(), rank));
+        self
+    }
+
+    #[inline]
+    pub fn yield_(&mut self, expr: &str) -> &mut Self {
+        self.columns.push(expr, None);
+        self
+    }
+
+    #[inline]
+    pub fn yield_as(&mut self, expr: &str, alias: &str) -> &mut Self {
+        self.columns.push(expr, Some(alias));
+        self
+    }
+}
+
+impl Sentence for Fetch {
+    fn sentence(&self) -> Result<String> {
+        if !self.keys.is_empty() && !matches!(self.target, FetchTarget::Edges { .. }) {
+            return Err(Error::Statement(String::from(
+                "`edge` only adds the edges to fetch by `Fetch::edges`",
+            )));
+        }
+        let mut sentence = match &self.target {
+            FetchTarget::Vertices { tag_names, from } => {
+                format!("FETCH PROP ON {} {}", names(tag_names, ", ")?, from.render()?)
+            }
+            FetchTarget::Edges { edge_name } => {
+                if self.keys.is_empty() {
+                    return Err(Error::Statement(String::from("no edge to fetch, add them by `edge`")));
+                }
+                let keys = self
+                    .keys
+                    .iter()
+                    .map(|(src, dst, rank)| {
+                        Ok(format!("{} -> {}@{}", value_literal(src)?, value_literal(dst)?, rank))
+                    })
+                    .collect::<Result<Vec<String>>>()?;
+                format!(
+                    "FETCH PROP ON {} {}",
+                    escape::quote_identifier(edge_name)?,
+                    keys.join(", ")
+                )
+            }
+            FetchTarget::EdgeRefs { edge_name, src, dst, rank } => {
+                let mut sentence = format!(
+                    "FETCH PROP ON {} {} -> {}",
+                    escape::quote_identifier(edge_name)?,
+                    src.reference()?,
+                    dst.reference()?
+                );
+                if let Some(rank) = rank {
+                    sentence += "@";
+                    sentence += rank.reference()?.as_str();
+                }
+                sentence
+            }
+        };
+        if !self.columns.is_empty() {
+            sentence += " YIELD ";
+            sentence += self.columns.render()?.as_str();
+        }
+        Ok(sentence)
+    }
+}
+
+/// `LOOKUP ON tag_or_edge [WHERE ..] YIELD ..` by the index
+/// 通过索引查找的 `LOOKUP` 语句
+#[derive(Debug, Clone)]
+pub struct Lookup {
+    name: String,
+    condition: Option<String>,
+    columns: Columns,
+}
+
+impl Lookup {
+    pub fn on(tag_or_edge_name: &str) -> Self {
+        Lookup {
+            name: tag_or_edge_name.to_string(),
+            condition: None,
+            columns: Columns::default(),
+        }
+    }
+
+    #[inline]
+    pub fn where_(&mut self, condition: &str) -> &mut Self {
+        self.condition = Some(condition.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn yield_(&mut self, expr: &str) -> &mut Self {
+        self.columns.push(expr, None);
+        self
+    }
+
+    #[inline]
+    pub fn yield_as(&mut self, expr: &str, alias: &str) -> &mut Self {
+        self.columns.push(expr, Some(alias));
+        self
+    }
+}
+
+impl Sentence for Lookup {
+    fn sentence(&self) -> Result<String> {
+        let mut sentence = format!("LOOKUP ON {}", escape::quote_identifier(&self.name)?);
+        if let Some(condition) = &self.condition {
+            sentence += " WHERE ";
+            sentence += condition.as_str();
+        }
+        if !self.columns.is_empty() {
+            sentence += " YIELD ";
+            sentence += self.columns.render()?.as_str();
+        }
+        Ok(sentence)
+    }
+}
+
+/// Properties in pattern, `{name: "Tom"}`
+fn pattern_props(props: &[(String, Value)]) -> Result<String> {
+    if props.is_empty() {
+        return Ok(String::new());
+    }
+    let props = props
+        .iter()
+        .map(|(name, value)| Ok(format!("{}: {}", escape::quote_identifier(name)?, value_literal(value)?)))
+        .collect::<Result<Vec<String>>>()?;
+    Ok(format!("{{{}}}", props.join(", ")))
+}
+
+/// Vertex in the pattern of `MATCH`, `(alias:tag{prop: value})`
+/// `MATCH` 模式中的点
+#[derive(Debug, Clone, Default)]
+pub struct Node {
+    alias: Option<String>,
+    tag_names: Vec<String>,
+    props: Vec<(String, Value)>,
+}
+
+impl Node {
+    /// The vertex referred by `alias` in `WHERE` and `RETURN`, anonymous if it's empty
+    pub fn new(alias: &str) -> Self {
+        Node {
+            alias: if alias.is_empty() { None } else { Some(alias.to_string()) },
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn tag(&mut self, tag_name: &str) -> &mut Self {
+        self.tag_names.push(tag_name.to_string());
+        self
+    }
+
+    /// Match the vertex with the property value, the tag is required
+    #[inline]
+    pub fn prop<V: Into<Value>>(&mut self, name: &str, value: V) -> &mut Self {
+        self.props.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// The pattern `(alias:tag{prop: value})`
+    pub fn pattern(&self) -> Result<String> {
+        let mut pattern = String::from("(");
+        if let Some(alias) = &self.alias {
+            pattern += escape::quote_identifier(alias)?.as_str();
+        }
+        for tag_name in &self.tag_names {
+            pattern += ":";
+            pattern += escape::quote_identifier(tag_name)?.as_str();
+        }
+        pattern += pattern_props(&self.props)?.as_str();
+        pattern += ")";
+        Ok(pattern)
+    }
+}
+
+/// Edge in the pattern of `MATCH`, `-[alias:edge*min..max{prop: value}]->`
+/// `MATCH` 模式中的边
+#[derive(Debug, Clone)]
+pub struct Relationship {
+    alias: Option<String>,
+    edge_names: Vec<String>,
+    direction: Direction,
+    hops: Option<(u32, u32)>,
+    props: Vec<(String, Value)>,
+}
+
+impl Relationship {
+    /// The outgoing edge referred by `alias` in `WHERE` and `RETURN`, anonymous if it's empty
+    pub fn new(alias: &str) -> Self {
+        Relationship {
+            alias: if alias.is_empty() { None } else { Some(alias.to_string()) },
+            edge_names: Vec::new(),
+            direction: Direction::Out,
+            hops: None,
+            props: Vec::new(),
+        }
+    }
+
+    /// Match the edge type, any edge type if never called
+    #[inline]
+    pub fn edge_type(&mut self, edge_name: &str) -> &mut Self {
+        self.edge_names.push(edge_name.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn direction(&mut self, direction: Direction) -> &mut Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Variable length path with `min..max` hops
+    #[inline]
+    pub fn hops(&mut self, min: u32, max: u32) -> &mut Self {
+        self.hops = Some((min, max));
+        self
+    }
+
+    /// Match the edge with the property value
+    #[inline]
+    pub fn prop<V: Into<Value>>(&mut self, name: &str, value: V) -> &mut Self {
+        self.props.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// The pattern `-[alias:edge*min..max{prop: value}]->`
+    pub fn pattern(&self) -> Result<String> {
+        let mut pattern = String::new();
+        if self.direction == Direction::In {
+            pattern += "<";
+        }
+        pattern += "-[";
+        if let Some(alias) = &self.alias {
+            pattern += escape::quote_identifier(alias)?.as_str();
+        }
+        if !self.edge_names.is_empty() {
+            pattern += ":";
+            pattern += names(&self.edge_names, "|")?.as_str();
+        }
+        if let Some((min, max)) = self.hops {
+            pattern += format!("*{}..{}", min, max).as_str();
+        }
+        pattern += pattern_props(&self.props)?.as_str();
+        pattern += "]-";
+        if self.direction == Direction::Out {
+            pattern += ">";
+        }
+        Ok(pattern)
+    }
+}
+
+/// Part of the pattern in `MATCH`
+#[derive(Debug, Clone)]
+enum PatternPart {
+    Node(Node),
+    Relationship(Relationship),
+}
+
+/// `MATCH pattern [WHERE ..] RETURN .. [ORDER BY ..] [SKIP ..] [LIMIT ..]`,
+/// the pattern is built by alternate `node` and `relationship`
+/// `MATCH` 语句，模式由交替的 `node` 和 `relationship` 构成
+#[derive(Debug, Clone, Default)]
+pub struct Match {
+    pattern: Vec<PatternPart>,
+    condition: Option<String>,
+    columns: Columns,
+    order_by: Vec<(String, bool)>,
+    skip: Option<u64>,
+    limit: Option<u64>,
+}
+
+impl Match {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn node(&mut self, node: &Node) -> &mut Self {
+        self.pattern.push(PatternPart::Node(node.clone()));
+        self
+    }
+
+    #[inline]
+    pub fn relationship(&mut self, relationship: &Relationship) -> &mut Self {
+        self.pattern.push(PatternPart::Relationship(relationship.clone()));
+        self
+    }
+
+    #[inline]
+    pub fn where_(&mut self, condition: &str) -> &mut Self {
+        self.condition = Some(condition.to_string());
+        self
+    }
+
+    #[inline]
+    pub fn return_(&mut self, expr: &str) -> &mut Self {
+        self.columns.push(expr, None);
+        self
+    }
+
+    #[inline]
+    pub fn return_as(&mut self, expr: &str, alias: &str) -> &mut Self {
+        self.columns.push(expr, Some(alias));
+        self
+    }
+
+    #[inline]
+    pub fn order_by(&mut self, expr: &str, descending: bool) -> &mut Self {
+        self.order_by.push((expr.to_string(), descending));
+        self
+    }
+
+    #[inline]
+    pub fn skip(&mut self, skip: u64) -> &mut Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    #[inline]
+    pub fn limit(&mut self, limit: u64) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl Sentence for Match {
+    fn sentence(&self) -> Result<String> {
+        // (node)-[relationship]->(node) ...
+        let alternate = self.pattern.iter().enumerate().all(|(i, part)| match part {
+            PatternPart::Node(_) => i % 2 == 0,
+            PatternPart::Relationship(_) => i % 2 == 1,
+        });
+        if !alternate || !matches!(self.pattern.last(), Some(PatternPart::Node(_))) {
+            return Err(Error::Statement(String::from(
+                "the pattern should be nodes and relationships in turn, starting and ending with a node",
+            )));
+        }
+        let mut sentence = String::from("MATCH ");
+        for part in &self.pattern {
+            sentence += match part {
+                PatternPart::Node(node) => node.pattern()?,
+                PatternPart::Relationship(relationship) => relationship.pattern()?,
+            }
+            .as_str();
+        }
+        if let Some(condition) = &self.condition {
+            sentence += " WHERE ";
+            sentence += condition.as_str();
+        }
+        sentence += " RETURN ";
+        if self.columns.is_empty() {
+            sentence += "*";
+        } else {
+            sentence += self.columns.render()?.as_str();
+        }
+        if !self.order_by.is_empty() {
+            let order_by: Vec<String> = self
+                .order_by
+                .iter()
+                .map(|(expr, descending)| format!("{} {}", expr, if *descending { "DESC" } else { "ASC" }))
+                .collect();
+            sentence += " ORDER BY ";
+            sentence += order_by.join(", ").as_str();
+        }
+        if let Some(skip) = self.skip {
+            sentence += format!(" SKIP {}", skip).as_str();
+        }
+        if let Some(limit) = self.limit {
+            sentence += format!(" LIMIT {}", limit).as_str();
+        }
+        Ok(sentence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expressions() {
+        assert_eq!(prop(&["follow", "degree"]).unwrap(), "`follow`.`degree`");
+        assert_eq!(src_prop("player", "name").unwrap(), "$^.`player`.`name`");
+        assert_eq!(dst_prop("player", "name").unwrap(), "$$.`player`.`name`");
+        assert_eq!(input("id").unwrap(), "$-.`id`");
+        assert_eq!(var("v", "id").unwrap(), "$v.`id`");
+        assert_eq!(literal("a\"b").unwrap(), "\"a\\\"b\"");
+        assert!(matches!(var("a-b", "id"), Err(Error::Statement(_))));
+        assert!(matches!(prop(&["a`b"]), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_go() {
+        let mut go = Go::new(Source::vids(["player100"]));
+        go.steps_range(1, 2)
+            .over("follow")
+            .direction(Direction::Both)
+            .where_(&format!("{} > 90", prop(&["follow", "degree"]).unwrap()))
+            .distinct(true)
+            .yield_as(&dst_prop("player", "name").unwrap(), "name");
+        assert_eq!(
+            go.statement().unwrap(),
+            "GO 1 TO 2 STEPS FROM \"player100\" OVER `follow` BIDIRECT \
+             WHERE `follow`.`degree` > 90 YIELD DISTINCT $$.`player`.`name` AS `name`;"
+        );
+
+        let mut go = Go::new(Source::input("id"));
+        go.steps(2).over("follow").over("serve").direction(Direction::In).yield_("dst(edge)");
+        assert_eq!(
+            go.statement().unwrap(),
+            "GO 2 STEPS FROM $-.`id` OVER `follow`, `serve` REVERSELY YIELD dst(edge);"
+        );
+        assert_eq!(Go::new(Source::vids([1i64, 2i64])).statement().unwrap(), "GO FROM 1, 2 OVER *;");
+        assert!(matches!(Go::new(Source::var("1v", "id")).statement(), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_fetch() {
+        let mut fetch = Fetch::vertices(&["player"], Source::vids(["a", "b"]));
+        fetch.yield_("vertex");
+        assert_eq!(fetch.statement().unwrap(), "FETCH PROP ON `player` \"a\", \"b\" YIELD vertex;");
+        assert_eq!(
+            Fetch::vertices(&[], Source::var("v", "id")).statement().unwrap(),
+            "FETCH PROP ON * $v.`id`;"
+        );
+
+        let mut fetch = Fetch::edges("follow");
+        fetch.edge("a", "b", 0).edge("a", "c", 1).yield_as("edge", "e");
+        assert_eq!(
+            fetch.statement().unwrap(),
+            "FETCH PROP ON `follow` \"a\" -> \"b\"@0, \"a\" -> \"c\"@1 YIELD edge AS `e`;"
+        );
+        assert!(matches!(Fetch::edges("follow").statement(), Err(Error::Statement(_))));
+
+        let fetch = Fetch::edge_refs("follow", Source::input("s"), Source::input("d"), None);
+        assert_eq!(fetch.statement().unwrap(), "FETCH PROP ON `follow` $-.`s` -> $-.`d`;");
+        let fetch = Fetch::edge_refs("follow", Source::var("v", "s"), Source::var("v", "d"), Some(Source::var("v", "r")));
+        assert_eq!(fetch.statement().unwrap(), "FETCH PROP ON `follow` $v.`s` -> $v.`d`@$v.`r`;");
+        let fetch = Fetch::edge_refs("follow", Source::vids(["a"]), Source::input("d"), None);
+        assert!(matches!(fetch.statement(), Err(Error::Statement(_))));
+        // The edge key isn't dropped silently
+        let mut fetch = Fetch::edge_refs("follow", Source::input("s"), Source::input("d"), None);
+        fetch.edge("a", "b", 0);
+        assert!(matches!(fetch.statement(), Err(Error::Statement(_))));
+        let mut fetch = Fetch::vertices(&["player"], Source::vids(["a"]));
+        fetch.edge("a", "b", 0);
+        assert!(matches!(fetch.statement(), Err(Error::Statement(_))));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut lookup = Lookup::on("player");
+        lookup
+            .where_(&format!("{} == {}", prop(&["player", "name"]).unwrap(), literal("Tim").unwrap()))
+            .yield_as("id(vertex)", "id");
+        assert_eq!(
+            lookup.statement().unwrap(),
+            "LOOKUP ON `player` WHERE `player`.`name` == \"Tim\" YIELD id(vertex) AS `id`;"
+        );
+        assert_eq!(Lookup::on("follow").statement().unwrap(), "LOOKUP ON `follow`;");
+    }
+
+    #[test]
+    fn test_match() {
+        let mut v = Node::new("v");
+        v.tag("player").prop("name", "Tim");
+        let mut e = Relationship::new("e");
+        e.edge_type("follow").edge_type("serve").hops(1, 3).direction(Direction::In);
+        let mut statement = Match::new();
+        statement
+            .node(&v)
+            .relationship(&e)
+            .node(&Node::new(""))
+            .where_("v.player.age > 30")
+            .return_as("v", "p")
+            .order_by("p", true)
+            .skip(1)
+            .limit(10);
+        assert_eq!(
+            statement.statement().unwrap(),
+            "MATCH (`v`:`player`{`name`: \"Tim\"})<-[`e`:`follow`|`serve`*1..3]-() \
+             WHERE v.player.age > 30 RETURN v AS `p` ORDER BY p DESC SKIP 1 LIMIT 10;"
+        );
+        assert_eq!(Match::new().node(&Node::new("v")).statement().unwrap(), "MATCH (`v`) RETURN *;");
+
+        let relationship = Relationship::new("");
+        assert_eq!(relationship.pattern().unwrap(), "-[]->");
+        for parts in [
+            Match::new(),
+            Match::new().relationship(&relationship).clone(),
+            Match::new().node(&v).relationship(&relationship).clone(),
+            Match::new().node(&v).node(&v).clone(),
+            Match::new().node(&v).relationship(&relationship).relationship(&relationship).node(&v).clone(),
+        ] {
+            assert!(matches!(parts.statement(), Err(Error::Statement(_))), "{:?}", parts);
+        }
+    }
+
+    #[test]
+    fn test_pipe_and_assign() {
+        let mut go = Go::new(Source::vids(["a"]));
+        go.over("follow").yield_as("src(edge)", "s").yield_as("dst(edge)", "d");
+        let fetch = Fetch::edge_refs("follow", Source::input("s"), Source::input("d"), None);
+        assert_eq!(
+            pipe(&[&go, &fetch, &"YIELD count(*)"]).unwrap(),
+            "GO FROM \"a\" OVER `follow` YIELD src(edge) AS `s`, dst(edge) AS `d` \
+             | FETCH PROP ON `follow` $-.`s` -> $-.`d` | YIELD count(*);"
+        );
+        assert_eq!(
+            assign("edges", &go).unwrap(),
+            "$edges = GO FROM \"a\" OVER `follow` YIELD src(edge) AS `s`, dst(edge) AS `d`;"
+        );
+        assert!(matches!(assign("a b", &go), Err(Error::Statement(_))));
+        assert!(matches!(pipe(&[&go, &Fetch::edges("follow")]), Err(Error::Statement(_))));
+    }
+}
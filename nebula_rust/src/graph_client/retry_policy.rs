@@ -0,0 +1,224 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+/// How a session with `retry_connect` retries the request after its server session
+/// expired or its connection broke
+/// 开启 `retry_connect` 的会话在服务端会话过期或连接断开后如何重试请求
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// max count of retries for each request, 0 means never retry
+    pub max_retries: u32,
+    /// wait time in ms before the first retry, doubles on each retry
+    pub interval: u32,
+    /// also retry the statements not known as read-only when the connection broke,
+    /// they may be applied twice
+    pub retry_writes: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            interval: 100,
+            retry_writes: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn max_retries(&mut self, max_retries: u32) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    #[inline]
+    pub fn interval(&mut self, interval: u32) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    #[inline]
+    pub fn retry_writes(&mut self, retry_writes: bool) -> &mut Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// The time to wait before the retry, counting from 0
+    /// 第 `attempt` 次重试（从 0 开始）前的等待时间
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        std::time::Duration::from_millis((self.interval as u64) << attempt.min(5))
+    }
+
+    /// Whether the request could be retried after the connection broke
+    /// 连接断开后该请求是否可以重试
+    pub fn should_retry(&self, query: &str) -> bool {
+        self.retry_writes || is_read_only(query)
+    }
+}
+
+/// The statements which never change the data or schema
+const READ_ONLY_KEYWORDS: &[&str] = &[
+    "GO", "FETCH", "LOOKUP", "MATCH", "SHOW", "DESCRIBE", "DESC", "YIELD", "FIND", "GET", "USE",
+];
+
+/// Whether every statement in the query, including the piped ones, is a read,
+/// so executing it twice is harmless
+/// 查询中的每条语句（包括管道中的语句）是否都是只读的，重复执行不会产生影响
+pub fn is_read_only(query: &str) -> bool {
+    let statements = split_statements(query);
+    !statements.is_empty()
+        && statements.iter().all(|statement| {
+            let keyword = statement
+                .split(|c: char| !c.is_ascii_alphabetic())
+                .next()
+                .unwrap_or("");
+            READ_ONLY_KEYWORDS
+                .iter()
+                .any(|read| read.eq_ignore_ascii_case(keyword))
+        })
+}
+
+/// Split the query by `;` and `|` out of quotes and comments, the comments are removed
+/// and the empty statements are skipped.
+/// The backslash escapes only in strings, the label in backticks is kept as is
+fn split_statements(query: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut statement = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            statement.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && q != '`' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' | '`' => {
+                quote = Some(c);
+                statement.push(c);
+            }
+            ';' | '|' => statements.push(std::mem::take(&mut statement)),
+            // `# ..`, `// ..` and `-- ..` to the end of line
+            '#' => skip_line(&mut chars),
+            '/' | '-' if chars.peek() == Some(&c) => skip_line(&mut chars),
+            // `/* .. */`
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut star = false;
+                for c in chars.by_ref() {
+                    if star && c == '/' {
+                        break;
+                    }
+                    star = c == '*';
+                }
+                statement.push(' ');
+            }
+            _ => statement.push(c),
+        }
+    }
+    statements.push(statement);
+    statements
+        .into_iter()
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+fn skip_line(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(split_statements("a; b | c;"), vec!["a", "b", "c"]);
+        assert_eq!(split_statements(" ;; "), Vec::<String>::new());
+        assert_eq!(
+            split_statements("a \"x;y\" 'z|w'; b `l;m`"),
+            vec!["a \"x;y\" 'z|w'", "b `l;m`"]
+        );
+        assert_eq!(split_statements("a \"x\\\";y\"; b"), vec!["a \"x\\\";y\"", "b"]);
+        assert_eq!(
+            split_statements("a # x; y\nb; /* c; */ d // e; f\n-- g; h\n"),
+            vec!["a b", "d"]
+        );
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("GO FROM \"a\" OVER e YIELD dst(edge);"));
+        assert!(is_read_only("go from \"a\" over e yield dst(edge)"));
+        assert!(is_read_only("fetch prop on t \"a\"; Show spaces; describe tag t"));
+        assert!(is_read_only("MATCH (v:player) RETURN v LIMIT 10;"));
+        assert!(is_read_only("USE s; LOOKUP ON t YIELD id(vertex) AS id | FETCH PROP ON t $-.id"));
+        assert!(!is_read_only(""));
+        assert!(!is_read_only(";"));
+
+        assert!(!is_read_only("INSERT VERTEX t(name) VALUES \"a\":(\"b\");"));
+        assert!(!is_read_only("insert vertex t(name) values \"a\":(\"b\")"));
+        assert!(!is_read_only("USE s; INSERT VERTEX t(name) VALUES \"a\":(\"b\");"));
+        assert!(!is_read_only("USE s; DELETE VERTEX \"a\""));
+        assert!(!is_read_only("LOOKUP ON t YIELD id(vertex) AS id | DELETE VERTEX $-.id"));
+        assert!(!is_read_only("GO FROM \"a\" OVER e; UPDATE VERTEX ON t \"a\" SET n = 1"));
+        assert!(!is_read_only("EXPLAIN GO FROM \"a\" OVER e"));
+    }
+
+    #[test]
+    fn test_is_read_only_quotes() {
+        // `;` and `|` in quotes don't split
+        assert!(is_read_only("FETCH PROP ON t \"a; DELETE VERTEX 1\""));
+        assert!(is_read_only("FETCH PROP ON t 'a | DELETE VERTEX 1'"));
+        assert!(is_read_only("FETCH PROP ON t \"a\\\"; DELETE VERTEX 1\""));
+        assert!(is_read_only("GO FROM \"a\" OVER `e; DELETE VERTEX 1`"));
+        // The backslash doesn't escape the backtick
+        assert!(!is_read_only("GO FROM \"a\" OVER `e\\`; DELETE VERTEX \"a\""));
+    }
+
+    #[test]
+    fn test_is_read_only_comments() {
+        assert!(is_read_only("/* DELETE VERTEX 1; */ GO FROM \"a\" OVER e"));
+        assert!(is_read_only("GO FROM \"a\" OVER e # ; DELETE VERTEX 1"));
+        assert!(is_read_only("-- INSERT\nGO FROM \"a\" OVER e"));
+        // The quote in comment doesn't hide the next statement
+        assert!(!is_read_only("GO FROM \"a\" OVER e /* \" */; DELETE VERTEX \"a\""));
+        assert!(!is_read_only("GO FROM \"a\" OVER e // '\n; DELETE VERTEX \"a\""));
+        assert!(!is_read_only("GO FROM \"a\" OVER e # `\n| DELETE VERTEX $-.id"));
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let mut policy = RetryPolicy::new();
+        assert!(policy.should_retry("GO FROM \"a\" OVER e"));
+        assert!(!policy.should_retry("INSERT VERTEX t() VALUES \"a\":()"));
+        policy.retry_writes(true);
+        assert!(policy.should_retry("INSERT VERTEX t() VALUES \"a\":()"));
+
+        policy.interval(100);
+        assert_eq!(policy.backoff(0), std::time::Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), std::time::Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), std::time::Duration::from_millis(3200));
+    }
+}
@@ -5,67 +5,293 @@
  */
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use common::types::Value;
+
+use crate::error::{Error, Result};
 use crate::graph_client::connection::Connection;
 use crate::graph_client::connection_pool::ConnectionPool_nebula;
+use crate::graph_client::escape;
+use crate::graph_client::nebula_schema;
 use crate::graph_client::nebula_schema::Tag;
+use crate::graph_client::nebula_schema::Ttl;
 use crate::graph_client::nebula_schema::ColType;
 use crate::graph_client::nebula_schema::InsertTagQuery;
 use crate::graph_client::nebula_schema::InsertEdgeQueryWithRank;
+use crate::graph_client::nebula_schema::{BatchLimit, BatchResult, StatementBatch};
+use crate::graph_client::nebula_schema::{DeleteEdgeQuery, DeleteTagQuery, DeleteVertexQuery, UpdateQuery};
+use crate::value::result_set::ResultSet;
 
-pub struct Session<'a> {
+/// The state replaced when the session reconnects
+/// 会话重连时被替换的状态
+struct SessionState {
     session_id: i64,
     conn: Connection,
-    pool: &'a ConnectionPool_nebula,
+    // The error of last failed reconnecting, returned if the connection is still lost
+    reconnect_error: Option<Error>,
+}
+
+/// The session owns a handle to the pool, so it could be moved into spawned tasks
+/// 会话持有连接池的句柄，因此可以移动到 spawn 的任务中
+pub struct Session {
+    // Requests in one session are serialized
+    state: tokio::sync::Mutex<SessionState>,
+    pool: Arc<ConnectionPool_nebula>,
+    // The current space tracked from responses and restored after reconnecting,
+    // empty means none
+    space: std::sync::Mutex<String>,
     username: String,
     password: String,
     // empty means not a named timezone
     time_zone_name: String,
     // Offset to utc in seconds
     offset_secs: i32,
-    // Keep connection if true
+    // Reconnect and retry by the `retry_policy` of pool if true
     retry_connect: bool,
+    // Released after the connection is given back to pool
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+    // The response of a timed out request may still arrive, so the connection is replaced
+    // before sending the next request
+    broken: std::sync::atomic::AtomicBool,
 }
 
-impl<'a> Session<'a> {
+impl Session {
     pub fn new(
         session_id: i64,
         conn: Connection,
-        pool: &'a ConnectionPool_nebula,
+        pool: Arc<ConnectionPool_nebula>,
         username: String,
         password: String,
         time_zone_name: String,
         offset_secs: i32,
         retry_connect: bool,
+        permit: tokio::sync::OwnedSemaphorePermit,
     ) -> Self {
+        let space = pool.config().space.clone();
         Session {
-            session_id: session_id,
-            conn: conn,
+            state: tokio::sync::Mutex::new(SessionState {
+                session_id,
+                conn,
+                reconnect_error: None,
+            }),
             pool: pool,
+            space: std::sync::Mutex::new(space),
             username: username,
             password: password,
             time_zone_name: time_zone_name,
             offset_secs: offset_secs,
             retry_connect: retry_connect,
+            permit: Some(permit),
+            broken: std::sync::atomic::AtomicBool::new(false),
         }
     }
 
     /// sign out the session
     #[inline]
-    pub async fn signout(&self) -> std::result::Result<(), common::types::ErrorCode> {
-        self.conn.signout(self.session_id).await
+    pub async fn signout(&self) -> Result<()> {
+        let state = self.state.lock().await;
+        if state.conn.is_null() {
+            return Err(not_connected());
+        }
+        state.conn.signout(state.session_id).await
+    }
+
+    /// Sign out the session and give back the connection to pool
+    /// The connection is discarded instead of given back if sign out failed
+    /// 登出会话并归还连接，登出失败时丢弃该连接
+    pub async fn release(mut self) -> Result<()> {
+        let state = self.state.get_mut();
+        let conn = std::mem::take(&mut state.conn);
+        if conn.is_null() {
+            // The connection was lost while reconnecting, it's released already
+            return Ok(());
+        }
+        let result = conn.signout(state.session_id).await;
+        self.pool.release_connection(conn, result.is_ok() && !self.is_broken());
+        result
+    }
+
+    #[inline]
+    fn is_broken(&self) -> bool {
+        self.broken.load(std::sync::atomic::Ordering::Acquire)
     }
 
     /// Execute the query in current session
     /// The returned error of `Result` only means the request/response status
     /// The error from Nebula Graph is still in `error_code` field in response, so you need check it
     /// to known wether the query execute succeeded
-    #[inline]
+    /// With `retry_connect`, the session reconnects and re-authenticates when its server session
+    /// expired or its connection broke, then retries the query by the `retry_policy` of pool.
+    /// The query which may change data is not retried after the connection broke,
+    /// unless `retry_writes` is set
+    /// 开启 `retry_connect` 时，服务端会话过期或连接断开后会话重新连接并认证，然后按连接池的
+    /// `retry_policy` 重试查询。连接断开后不会重试可能修改数据的查询，除非设置了 `retry_writes`
     pub async fn execute(
         &self,
         query: &str,
-    ) -> std::result::Result<graph::types::ExecutionResponse, common::types::ErrorCode> {
-        self.conn.execute(self.session_id, query).await
+    ) -> Result<graph::types::ExecutionResponse> {
+        let mut state = self.state.lock().await;
+        self.execute_locked(&mut state, query).await
+    }
+
+    /// Execute the query in the space, the state lock is held from `USE` to the end of query,
+    /// so the space can't be switched by other tasks in between
+    /// 在该图空间中执行查询，从 `USE` 到查询结束一直持有状态锁，其他任务无法在中间切换图空间
+    pub async fn execute_in_space(
+        &self,
+        space: &str,
+        query: &str,
+    ) -> Result<graph::types::ExecutionResponse> {
+        let mut state = self.state.lock().await;
+        self.switch_space_locked(&mut state, space).await?;
+        self.execute_locked(&mut state, query).await
+    }
+
+    /// Execute the query in the space and check the `error_code` in response, see `execute_in_space`
+    /// 在该图空间中执行查询并检查响应中的 `error_code`
+    #[inline]
+    pub async fn query_in_space(&self, space: &str, query: &str) -> Result<ResultSet> {
+        ResultSet::from_response(self.execute_in_space(space, query).await?)
+    }
+
+    /// `query_in_space` retried up to `schema_wait_timeout` of the pool while the tag or edge is missing
+    async fn query_retrying_schema(&self, space: &str, query: &str) -> Result<ResultSet> {
+        let timeout = self.pool.config().schema_wait_timeout;
+        nebula_schema::retry_while_schema_missing(timeout, || self.query_in_space(space, query)).await
+    }
+
+    async fn execute_locked(
+        &self,
+        state: &mut SessionState,
+        query: &str,
+    ) -> Result<graph::types::ExecutionResponse> {
+        if self.is_broken() {
+            self.reconnect(state, false).await;
+        }
+        let policy = &self.pool.config().retry_policy;
+        let mut attempt = 0;
+        loop {
+            let (result, sent) = if state.conn.is_null() {
+                (Err(state.reconnect_error.take().unwrap_or_else(not_connected)), false)
+            } else {
+                (state.conn.execute(state.session_id, query).await, true)
+            };
+            let connection_lost = match &result {
+                Ok(resp) if !is_session_expired(resp.error_code) => {
+                    self.track_space(resp);
+                    return result;
+                }
+                Ok(_) => false,
+                Err(e) if e.is_connection_error() => true,
+                Err(_) => return result,
+            };
+            if !self.retry_connect || attempt >= policy.max_retries {
+                return result;
+            }
+            tokio::time::sleep(policy.backoff(attempt)).await;
+            attempt += 1;
+            self.reconnect(state, !connection_lost).await;
+            // The query may be applied already before the connection broke
+            if connection_lost && sent && !policy.should_retry(query) {
+                return result;
+            }
+        }
+    }
+
+    /// Replace the connection and server session, the error is kept in state if failed.
+    /// The broken connection is never given back
+    async fn reconnect(&self, state: &mut SessionState, usable: bool) {
+        let old = std::mem::take(&mut state.conn);
+        let usable = usable && !self.is_broken();
+        match self
+            .pool
+            .reconnect(old, usable, &self.username, &self.password, &self.space())
+            .await
+        {
+            Ok((conn, session_id)) => {
+                state.conn = conn;
+                state.session_id = session_id;
+                self.broken.store(false, std::sync::atomic::Ordering::Release);
+            }
+            Err(e) => state.reconnect_error = Some(e),
+        }
+    }
+
+    /// Remember the space which the server reports the session is using
+    fn track_space(&self, resp: &graph::types::ExecutionResponse) {
+        if let Some(space) = resp.space_name.as_ref().filter(|space| !space.is_empty()) {
+            *self.space.lock().unwrap() = String::from_utf8_lossy(space).into_owned();
+        }
+    }
+
+    /// Get the current space, empty if not chosen yet
+    /// 获取当前图空间，尚未选择时为空
+    #[inline]
+    pub fn space(&self) -> String {
+        self.space.lock().unwrap().clone()
+    }
+
+    /// Switch to the space, nothing is sent if it's the current one.
+    /// The space is restored after the session reconnects, but it may be switched by other tasks
+    /// sharing the session, use `execute_in_space` to run the query in the space
+    /// 切换到该图空间，已是当前图空间时不发送请求，会话重连后会恢复该图空间，
+    /// 但共享该会话的其他任务可能再次切换，需要在该图空间中执行查询时使用 `execute_in_space`
+    pub async fn switch_space(&self, space: &str) -> Result<()> {
+        let mut state = self.state.lock().await;
+        self.switch_space_locked(&mut state, space).await
+    }
+
+    async fn switch_space_locked(&self, state: &mut SessionState, space: &str) -> Result<()> {
+        if self.space() == space {
+            return Ok(());
+        }
+        let query = format!("USE {};", escape::quote_identifier(space)?);
+        ResultSet::from_response(self.execute_locked(state, &query).await?)?;
+        *self.space.lock().unwrap() = space.to_string();
+        Ok(())
+    }
+
+    /// Get the `use` statement of the space, the name isn't quoted
+    #[deprecated(note = "the name isn't quoted, use `switch_space` or `execute_in_space` instead")]
+    #[inline]
+    pub fn use_space(space_name: &str) -> String{
+        let mut line = String::from("use ");
+        line += space_name;
+        line += ";";
+        line
+    }
+
+    /// Execute the query in current session and check the `error_code` in response,
+    /// return the error with message from server if the query failed
+    /// 在当前会话中执行查询并检查响应中的 `error_code`，执行失败时返回带有服务端错误信息的错误
+    #[inline]
+    pub async fn query(&self, query: &str) -> Result<ResultSet> {
+        ResultSet::from_response(self.execute(query).await?)
+    }
+
+    /// Execute the query with a timeout in ms for this call only,
+    /// it could only be shorter than the `request_timeout` of the pool.
+    /// The wait for other calls sharing the session isn't counted. The connection is marked
+    /// broken if the call timed out and replaced by the next call, otherwise the late response
+    /// could be read as the reply to the next query
+    /// 以仅对本次调用生效的超时（毫秒）执行查询，只能比连接池的 `request_timeout` 更短，
+    /// 等待共享该会话的其他调用的时间不计入超时。超时后该连接被标记为损坏并由下一次调用替换，
+    /// 避免迟到的响应被当作下一个查询的回复
+    pub async fn execute_with_timeout(
+        &self,
+        query: &str,
+        timeout: u32,
+    ) -> Result<graph::types::ExecutionResponse> {
+        let timeout = std::time::Duration::from_millis(timeout as u64);
+        let mut state = self.state.lock().await;
+        match tokio::time::timeout(timeout, self.execute_locked(&mut state, query)).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.broken.store(true, std::sync::atomic::Ordering::Release);
+                Err(Error::Timeout(format!("execute `{}`", query)))
+            }
+        }
     }
 
     /// Get the time zone name
@@ -79,18 +305,18 @@ impl<'a> Session<'a> {
     pub fn offset_secs(&self) -> i32 {
         self.offset_secs
     }
+    /// List the spaces, the names are in the result set
+    /// 列出所有图空间，名称在结果集中
     #[inline]
-    pub async fn show_spaces(&self){
-        let query = "show spaces;";
-        let resp = self.execute(query).await.unwrap();
-        resp.show_data();
+    pub async fn show_spaces(&self) -> Result<ResultSet> {
+        self.query("show spaces;").await
     }
 
     #[inline]
-    pub fn get_create_space_query(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str) -> String{
-        let mut query = String::from("CREATE SPACE IF NOT EXISTS `");
-        query += space_name;
-        query += "` (partition_num = ";
+    pub fn get_create_space_query(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str) -> Result<String>{
+        let mut query = String::from("CREATE SPACE IF NOT EXISTS ");
+        query += escape::quote_identifier(space_name)?.as_str();
+        query += " (partition_num = ";
         query += partition_num.to_string().as_str();
         query += ", replica_factor = ";
         query += replica_factor.to_string().as_str();
@@ -103,71 +329,62 @@ impl<'a> Session<'a> {
             query += "INT64)";
         }
         if comment!=""{
-            query += " COMMENT = \"";
-            query += comment;
-            query += "\"";
+            query += " COMMENT = ";
+            query += escape::quote_string(comment).as_str();
         }
         query += ";";
-        query
+        Ok(query)
     }
     #[inline]
     // CREATE SPACE `testGraph` (partition_num = 15, replica_factor = 1, vid_type = FIXED_STRING(50)) COMMENT = "this is a graph for test"
-    pub async fn create_space(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str){
-        let query = self.get_create_space_query(space_name, partition_num, replica_factor, is_fixed_string, fixed_string_len, comment);
+    pub async fn create_space(&self, space_name: &str, partition_num: u8, replica_factor: u8, is_fixed_string: bool, fixed_string_len: u8, comment: &str) -> Result<ResultSet> {
+        let query = self.get_create_space_query(space_name, partition_num, replica_factor, is_fixed_string, fixed_string_len, comment)?;
         // println!("{}", query);
-        let _resp = self.execute(query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query(query.as_str()).await
     }
 
+    /// The statement switches to the space first, like `Connection::get_create_tag_or_edge`
     #[inline]
-    pub fn get_create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>) -> String{
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; CREATE ";
-        query += col_type.to_string().as_str();
-        query += " IF NOT EXISTS `";
-        query += tag_name;
-        query += "` (";
-        for i in 0..tags.len() {
-            query += tags[i].to_string().as_str();
-            if i!=tags.len()-1{
-                query += ",";
-            }
-        }
-        query += ")";
-        if comment!="".to_string(){
-            query += " COMMENT = \"";
-            query += comment;
-            query += "\"";
-        }
-        query += ";";
-        query
+    pub fn get_create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>) -> Result<String>{
+        Connection::create_tag_or_edge_query(space_name, col_type, tag_name, comment, &tags, None)
     }
 
+    /// The statement switches to the space first, like `Connection::get_create_tag_or_edge_with_ttl`
     #[inline]
-    pub async fn create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>){
+    pub fn get_create_tag_or_edge_with_ttl(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, ttl: &Ttl) -> Result<String>{
+        Connection::create_tag_or_edge_query(space_name, col_type, tag_name, comment, &tags, Some(ttl))
+    }
 
-        let query = self.get_create_tag_or_edge(space_name, col_type, tag_name, comment, tags);
+    #[inline]
+    pub async fn create_tag_or_edge(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>) -> Result<ResultSet> {
+
+        let query = nebula_schema::create_schema_statement(col_type, tag_name, comment, &tags, None)?;
         //println!("{}", query);
 
-        let _resp = self.execute(query.as_str()).await.unwrap();
+        self.query_in_space(space_name, query.as_str()).await
+    }
 
-        //println!("{:?}", _resp);
+    /// Create the tag or edge whose vertices or edges expire by the `ttl`
+    /// 创建带有存活时间的标签或边类型
+    pub async fn create_tag_or_edge_with_ttl(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, ttl: &Ttl) -> Result<ResultSet> {
+        let query = nebula_schema::create_schema_statement(col_type, tag_name, comment, &tags, Some(ttl))?;
+        self.query_in_space(space_name, query.as_str()).await
+    }
+
+    /// Create the tag or edge, then wait until it's visible by `wait_for_schema`
+    /// 创建标签或边类型，并等待其可见
+    pub async fn create_tag_or_edge_and_wait(&self, space_name: &str, col_type: ColType, tag_name: &str, comment: &str, tags: Vec<Tag>, timeout: u32) -> Result<ResultSet> {
+        let resp = self.create_tag_or_edge(space_name, col_type, tag_name, comment, tags).await?;
+        self.wait_for_schema(space_name, tag_name, col_type, timeout).await?;
+        Ok(resp)
     }
 
     #[inline]
     // INSERT VERTEX t2 (name, age) VALUES "11":("n1", 12);
-    pub async fn insert_tag(&self, space_name: &str, tag_name: &str, kv: HashMap<String, String>, vid: &str){
-
-        if self.find_tag_or_edge(space_name, tag_name, ColType::Tag).await == false{
-            std::thread::sleep(std::time::Duration::from_millis(5000));
-        }
+    pub async fn insert_tag(&self, space_name: &str, tag_name: &str, kv: HashMap<String, Value>, vid: &str) -> Result<ResultSet> {
 
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
-        query += "INSERT VERTEX IF NOT EXISTS ";
-        query += tag_name;
+        let mut query = String::from("INSERT VERTEX IF NOT EXISTS ");
+        query += escape::quote_identifier(tag_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -176,50 +393,93 @@ impl<'a> Session<'a> {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += vid;
-        query += "\":";
+        query += " VALUES ";
+        query += escape::quote_string(vid).as_str();
+        query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query_retrying_schema(space_name, &query).await
     }
 
     #[inline]
-    pub async fn insert_tags(&self, insert_tag_queries: Vec<InsertTagQuery>){
+    pub async fn insert_tags(&self, insert_tag_queries: Vec<InsertTagQuery>) -> Result<Vec<ResultSet>> {
+        let mut results = Vec::with_capacity(insert_tag_queries.len());
         for query in insert_tag_queries{
-            self.insert_tag(query.space_name.as_str(), query.tag_name.as_str(), query.kv, query.vid.as_str()).await;
+            results.push(self.insert_tag(query.space_name.as_str(), query.tag_name.as_str(), query.kv, query.vid.as_str()).await?);
         }
+        Ok(results)
     }
 
     #[inline]
-    pub async fn insert_edges(&self, insert_edge_queries: Vec<InsertEdgeQueryWithRank>){
+    pub async fn insert_edges(&self, insert_edge_queries: Vec<InsertEdgeQueryWithRank>) -> Result<Vec<ResultSet>> {
+        let mut results = Vec::with_capacity(insert_edge_queries.len());
         for query in insert_edge_queries{
-            self.insert_edge_with_rank(query.space_name.as_str(), query.edge_name.as_str(), query.kv, query.from_vertex.as_str(), query.to_vertex.as_str(), query.rank).await;
+            results.push(self.insert_edge_with_rank(query.space_name.as_str(), query.edge_name.as_str(), query.kv, query.from_vertex.as_str(), query.to_vertex.as_str(), query.rank).await?);
         }
+        Ok(results)
     }
 
-    #[inline]
-    // INSERT EDGE e2 (name, age) VALUES "11"->"13":("n1", 12);
-    pub async fn insert_edge(&self, space_name: &str, edge_name: &str, kv: HashMap<String, String>, from_vertex: &str, to_vertex: &str){
+    /// Insert the vertices in batches, see `nebula_schema::batch_insert_tags`.
+    /// Unlike `insert_tags`, it doesn't check the tags exist, and all batches are sent even if some fail
+    /// 分批插入点，与 `insert_tags` 不同，不检查标签是否存在，部分批次失败时仍发送所有批次
+    pub async fn insert_tags_batched(&self, insert_tag_queries: &[InsertTagQuery], if_not_exists: bool, limit: &BatchLimit) -> Result<Vec<BatchResult>> {
+        Ok(self.send_batches(nebula_schema::batch_insert_tags(insert_tag_queries, if_not_exists, limit)?).await)
+    }
+
+    /// Insert the edges in batches, see `nebula_schema::batch_insert_edges`.
+    /// Unlike `insert_edges`, it doesn't check the edges exist, and all batches are sent even if some fail
+    /// 分批插入边，与 `insert_edges` 不同，不检查边类型是否存在，部分批次失败时仍发送所有批次
+    pub async fn insert_edges_batched(&self, insert_edge_queries: &[InsertEdgeQueryWithRank], if_not_exists: bool, limit: &BatchLimit) -> Result<Vec<BatchResult>> {
+        Ok(self.send_batches(nebula_schema::batch_insert_edges(insert_edge_queries, if_not_exists, limit)?).await)
+    }
 
-        if self.find_tag_or_edge(space_name, edge_name, ColType::Edge).await == false{
-            std::thread::sleep(std::time::Duration::from_millis(5000));
+    async fn send_batches(&self, batches: Vec<StatementBatch>) -> Vec<BatchResult> {
+        let mut results = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let result = self.query_in_space(&batch.space_name, &batch.statement).await;
+            results.push(BatchResult { batch, result });
         }
+        results
+    }
+
+    /// Update or upsert the vertex or edge, the yielded properties are in the result set
+    /// 更新或插入更新点、边，`YIELD` 的属性在结果集中
+    pub async fn update(&self, query: &UpdateQuery) -> Result<ResultSet> {
+        self.query_in_space(&query.space_name, &query.statement()?).await
+    }
 
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
-        query += "INSERT EDGE IF NOT EXISTS ";
-        query += edge_name;
+    /// Delete the vertices in batches, all batches are sent even if some fail
+    /// 分批删除点，部分批次失败时仍发送所有批次
+    pub async fn delete_vertices(&self, query: &DeleteVertexQuery, limit: &BatchLimit) -> Result<Vec<BatchResult>> {
+        Ok(self.send_batches(query.batches(limit)?).await)
+    }
+
+    /// Delete the edges in batches, all batches are sent even if some fail
+    /// 分批删除边，部分批次失败时仍发送所有批次
+    pub async fn delete_edges(&self, query: &DeleteEdgeQuery, limit: &BatchLimit) -> Result<Vec<BatchResult>> {
+        Ok(self.send_batches(query.batches(limit)?).await)
+    }
+
+    /// Delete the tags from vertices in batches, all batches are sent even if some fail
+    /// 分批删除点上的标签，部分批次失败时仍发送所有批次
+    pub async fn delete_tags(&self, query: &DeleteTagQuery, limit: &BatchLimit) -> Result<Vec<BatchResult>> {
+        Ok(self.send_batches(query.batches(limit)?).await)
+    }
+
+    #[inline]
+    // INSERT EDGE e2 (name, age) VALUES "11"->"13":("n1", 12);
+    pub async fn insert_edge(&self, space_name: &str, edge_name: &str, kv: HashMap<String, Value>, from_vertex: &str, to_vertex: &str) -> Result<ResultSet> {
+
+        let mut query = String::from("INSERT EDGE IF NOT EXISTS ");
+        query += escape::quote_identifier(edge_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -228,33 +488,30 @@ impl<'a> Session<'a> {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += from_vertex;
-        query += "\" -> \"";
-        query += to_vertex;
-        query += "\":";
+        query += " VALUES ";
+        query += escape::quote_string(from_vertex).as_str();
+        query += " -> ";
+        query += escape::quote_string(to_vertex).as_str();
+        query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(query.as_str()).await.unwrap();
+        self.query_retrying_schema(space_name, &query).await
     }
 
 
     #[inline]
     // INSERT EDGE e2 (name, age) VALUES "11"->"13"@1:("n1", 12);
-    pub async fn insert_edge_with_rank(&self, space_name: &str, edge_name: &str, kv: HashMap<String, String>, from_vertex: &str, to_vertex: &str, rank: i64){
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
-        query += "INSERT EDGE IF NOT EXISTS ";
-        query += edge_name;
+    pub async fn insert_edge_with_rank(&self, space_name: &str, edge_name: &str, kv: HashMap<String, Value>, from_vertex: &str, to_vertex: &str, rank: i64) -> Result<ResultSet> {
+        let mut query = String::from("INSERT EDGE IF NOT EXISTS ");
+        query += escape::quote_identifier(edge_name)?.as_str();
         query += " ";
         let mut keys = String::from("(");
         let mut values = String::from("(");
@@ -263,50 +520,43 @@ impl<'a> Session<'a> {
                 keys += ",";
                 values += ",";
             }
-            keys += k.as_str();
-            values += v.as_str();
+            keys += escape::quote_identifier(k.as_str())?.as_str();
+            values += nebula_schema::value_literal(&v)?.as_str();
         }
         keys += ")";
         values += ")";
         query += keys.as_str();
-        query += " VALUES \"";
-        query += from_vertex;
-        query += "\" -> \"";
-        query += to_vertex;
-        query += "\"@";
+        query += " VALUES ";
+        query += escape::quote_string(from_vertex).as_str();
+        query += " -> ";
+        query += escape::quote_string(to_vertex).as_str();
+        query += "@";
         query += rank.to_string().as_str();
         query += ":";
         query += values.as_str();
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(query.as_str()).await.unwrap();
-    
-        println!("{:?}", _resp);
+        self.query_retrying_schema(space_name, &query).await
     }
 
     #[inline]
     // CREATE TAG INDEX `index_tag` on `stu`      (`name`(10), `age`) COMMENT "this is an index for tag"
-    pub async fn create_index(&self, space_name: &str, index_type: ColType, tag_or_edge_name: &str, index_name: &str, comment: &str, indexed_properties: HashMap<String, u8>){
-        let mut query = String::from("use ");
-        query += space_name;
-        query += "; ";
-        query += "CREATE ";
+    pub async fn create_index(&self, space_name: &str, index_type: ColType, tag_or_edge_name: &str, index_name: &str, comment: &str, indexed_properties: HashMap<String, u8>) -> Result<ResultSet> {
+        let mut query = String::from("CREATE ");
         query += index_type.to_string().as_str();
-        query += " INDEX `";
-        query += index_name;
-        query += "` on `";
-        query += tag_or_edge_name;
-        query += "`(";
+        query += " INDEX ";
+        query += escape::quote_identifier(index_name)?.as_str();
+        query += " on ";
+        query += escape::quote_identifier(tag_or_edge_name)?.as_str();
+        query += "(";
 
         let mut properties = String::from("");
         for (k,v) in indexed_properties {
             if properties.len()!=0{
                 properties += ",";
             }
-            let mut property = String::from("`");
-            property += k.as_str();
-            property += "`";
+            let mut property = escape::quote_identifier(k.as_str())?;
             if v==0 {
             }else{
                 property += "(";
@@ -319,55 +569,82 @@ impl<'a> Session<'a> {
         query += properties.as_str();
         query += ") ";
         if comment!=""{
-            query += "COMMENT \"";
-            query += comment;
-            query += "\"";
+            query += "COMMENT ";
+            query += escape::quote_string(comment).as_str();
         }
         query += ";";
 
         // println!("{}", query);
-        let _resp = self.execute(query.as_str()).await.unwrap();
-        // println!("{:?}", _resp);
+        self.query_in_space(space_name, query.as_str()).await
     }
 
     #[inline]
-    pub async fn find_tag_or_edge(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType) -> bool{
-        let mut query = Self::use_space(space_name);
-        match col_type {
-            ColType::Edge => query += "show edges;",
-            ColType::Tag => query += "show tags;",
-        }
-        let resp = self.execute(query.as_str()).await.unwrap();
-        // print!("{:?}", resp);
-        let res = resp.get_sVal();
-        match res {
-            Some(tags) => {
-                for tag in tags{
-                    if tag == tag_or_edge_name.to_string(){
-                        return true;
-                    }
-                }
-            }
-            None => return false
-        }
-        return false;
+    pub async fn find_tag_or_edge(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType) -> Result<bool> {
+        let query = match col_type {
+            ColType::Edge => "show edges;",
+            ColType::Tag => "show tags;",
+        };
+        let resp = self.query_in_space(space_name, query).await?;
+        Ok(resp
+            .response()
+            .get_sVal()
+            .is_some_and(|names| names.iter().any(|name| name == tag_or_edge_name)))
     }
 
-    #[inline]
-    pub fn use_space(space_name: &str) -> String{
-        let mut line = String::from("use ");
-        line += space_name;
-        line += ";";
-        line
+    /// Wait until graphd and storaged can use the tag or edge, `SHOW TAGS/EDGES` of `find_tag_or_edge`
+    /// is answered by metad before them. Fail with `Error::Timeout` if it's still missing after `timeout` ms
+    /// 等待 graphd 与 storaged 可以使用标签或边类型，超过 `timeout` 毫秒仍缺失时返回超时错误
+    pub async fn wait_for_schema(&self, space_name: &str, tag_or_edge_name: &str, col_type: ColType, timeout: u32) -> Result<()> {
+        nebula_schema::wait_for_schema(space_name, tag_or_edge_name, col_type, timeout, |query| async move {
+            self.query_in_space(space_name, &query).await
+        })
+        .await
     }
 
 }
 
-impl<'a> Drop for Session<'a> {
+impl Drop for Session {
     /// Drop session will sign out the session in server
-    /// and give back connection to pool
+    /// and give back connection to pool in a background task,
+    /// so dropping never blocks the runtime thread.
+    /// Prefer `release` to know whether the sign out succeeded.
+    /// 在后台任务中登出会话并归还连接，drop 不会阻塞运行时线程
     fn drop(&mut self) {
-        futures::executor::block_on(self.signout());
-        self.pool.give_back(std::mem::take(&mut self.conn));
+        let state = self.state.get_mut();
+        let conn = std::mem::take(&mut state.conn);
+        if conn.is_null() {
+            // released already
+            return;
+        }
+        let session_id = state.session_id;
+        let pool = Arc::clone(&self.pool);
+        let permit = self.permit.take();
+        let broken = self.is_broken();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    let result = conn.signout(session_id).await;
+                    pool.release_connection(conn, result.is_ok() && !broken);
+                    drop(permit);
+                });
+            }
+            // Can't sign out without runtime, the server session will expire by itself
+            Err(_) => pool.release_connection(conn, false),
+        }
     }
 }
+
+/// The server session is gone, but the connection still works
+#[inline]
+fn is_session_expired(code: common::types::ErrorCode) -> bool {
+    code == common::types::ErrorCode::E_SESSION_INVALID
+        || code == common::types::ErrorCode::E_SESSION_TIMEOUT
+}
+
+#[inline]
+fn not_connected() -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::NotConnected,
+        "the session lost its connection",
+    ))
+}
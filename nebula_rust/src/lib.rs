@@ -4,5 +4,11 @@
  * attached with Common Clause Condition 1.0, found in the LICENSES directory.
  */
 
+pub mod error;
 pub mod graph_client;
 pub mod value;
+
+pub use crate::error::{Error, Result};
+
+// The thrift types used in the public API
+pub use common;
@@ -0,0 +1,22 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use crate::value::result_set::{OwnedRecord, Record};
+
+pub use nebula_rust_derive::FromRow;
+
+/// Construct the type from one row of result set, usually by `#[derive(FromRow)]`
+/// 由结果集中的一行构造该类型，通常通过 `#[derive(FromRow)]` 实现
+pub trait FromRow: Sized {
+    fn from_record(record: &Record<'_>) -> crate::Result<Self>;
+
+    /// Construct from the row owning its values, which could be moved instead of cloned.
+    /// It clones by `from_record` if not overridden
+    /// 由拥有其值的行构造，值可以被移动而非复制，未重写时通过 `from_record` 复制
+    fn from_owned_record(record: OwnedRecord<'_>) -> crate::Result<Self> {
+        Self::from_record(&record.as_record())
+    }
+}
@@ -6,4 +6,6 @@
 
 /// Some extension of the thrift value
 pub mod data_set;
+pub mod from_row;
+pub mod result_set;
 pub mod row;
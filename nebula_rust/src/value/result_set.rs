@@ -0,0 +1,404 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use std::convert::TryFrom;
+
+use common::convert::ConvertError;
+
+use crate::value::data_set::DataSet;
+use crate::value::from_row::FromRow;
+
+/// The result of a succeeded query
+/// 成功执行的查询结果
+#[derive(Debug, Clone)]
+pub struct ResultSet {
+    resp: graph::types::ExecutionResponse,
+}
+
+impl ResultSet {
+    /// Construct from the response of executing query
+    pub fn new(resp: graph::types::ExecutionResponse) -> Self {
+        ResultSet { resp }
+    }
+
+    /// Construct from the response, fail if the query failed
+    /// 由响应构造，查询执行失败时返回服务端的错误
+    pub fn from_response(resp: graph::types::ExecutionResponse) -> crate::Result<Self> {
+        if resp.error_code != common::types::ErrorCode::SUCCEEDED {
+            return Err(crate::Error::server(resp.error_code, resp.error_msg.as_deref()));
+        }
+        Ok(ResultSet::new(resp))
+    }
+
+    /// Get the data set, `None` if the query returns nothing
+    #[inline]
+    pub fn data(&self) -> Option<&common::types::DataSet> {
+        self.resp.data.as_ref()
+    }
+
+    /// Get names of columns, empty if the query returns nothing
+    /// 获取列名，查询没有返回数据时为空
+    pub fn column_names(&self) -> std::vec::Vec<std::borrow::Cow<'_, str>> {
+        self.data()
+            .map(|data| column_names(&data.column_names))
+            .unwrap_or_default()
+    }
+
+    /// Get count of rows
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data().map_or(0, |data| data.len())
+    }
+
+    /// Whether there is no row
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the row at index
+    /// 获取指定下标的行
+    pub fn row(&self, index: usize) -> Option<Record<'_>> {
+        let data = self.data()?;
+        data.rows
+            .get(index)
+            .map(|row| Record::new(&data.column_names, row))
+    }
+
+    /// Get the value in row at index by column name
+    /// 按列名获取指定行中的值
+    pub fn get(&self, index: usize, column: &str) -> Option<&common::types::Value> {
+        self.row(index)?.get(column)
+    }
+
+    /// Iterate over the rows
+    /// 遍历所有行
+    pub fn iter(&self) -> impl Iterator<Item = Record<'_>> {
+        self.data().into_iter().flat_map(|data| {
+            data.rows
+                .iter()
+                .map(move |row| Record::new(&data.column_names, row))
+        })
+    }
+
+    /// Convert each row into `T`, usually derived by `#[derive(FromRow)]`,
+    /// the values are moved into `T` instead of cloned
+    /// 将每一行转换为 `T`，通常通过 `#[derive(FromRow)]` 派生，值被移动而非复制
+    pub fn into_typed<T: FromRow>(self) -> crate::Result<std::vec::Vec<T>> {
+        let data = match self.resp.data {
+            Some(data) => data,
+            None => return Ok(std::vec::Vec::new()),
+        };
+        let column_names = data.column_names;
+        data.rows
+            .into_iter()
+            .map(|row| T::from_owned_record(OwnedRecord::new(&column_names, row)))
+            .collect()
+    }
+
+    /// Get the time spent by server
+    #[inline]
+    pub fn latency(&self) -> std::time::Duration {
+        std::time::Duration::from_micros(self.resp.latency_in_us.max(0) as u64)
+    }
+
+    /// Get the execution plan, only returned by `EXPLAIN` and `PROFILE`
+    #[inline]
+    pub fn plan_desc(&self) -> Option<&graph::types::PlanDescription> {
+        self.resp.plan_desc.as_ref()
+    }
+
+    /// Get the comment from server, e.g. the warning of query
+    #[inline]
+    pub fn comment(&self) -> Option<&str> {
+        self.resp
+            .comment
+            .as_ref()
+            .and_then(|comment| std::str::from_utf8(comment).ok())
+    }
+
+    /// Get the space which the session is using after the query
+    #[inline]
+    pub fn space_name(&self) -> Option<&str> {
+        self.resp
+            .space_name
+            .as_ref()
+            .and_then(|space| std::str::from_utf8(space).ok())
+    }
+
+    /// Get the raw response
+    #[inline]
+    pub fn response(&self) -> &graph::types::ExecutionResponse {
+        &self.resp
+    }
+
+    /// Take the raw response
+    #[inline]
+    pub fn into_response(self) -> graph::types::ExecutionResponse {
+        self.resp
+    }
+}
+
+impl<'a> IntoIterator for &'a ResultSet {
+    type Item = Record<'a>;
+    type IntoIter = Box<dyn Iterator<Item = Record<'a>> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// One row of result set, the values could be got by column name
+/// 结果集中的一行，可以按列名获取值
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    column_names: &'a [std::vec::Vec<u8>],
+    row: &'a common::types::Row,
+}
+
+impl<'a> Record<'a> {
+    fn new(column_names: &'a [std::vec::Vec<u8>], row: &'a common::types::Row) -> Self {
+        Record { column_names, row }
+    }
+
+    /// Get names of columns
+    #[inline]
+    pub fn column_names(&self) -> std::vec::Vec<std::borrow::Cow<'a, str>> {
+        column_names(self.column_names)
+    }
+
+    /// Get the value by column name
+    /// 按列名获取值
+    pub fn get(&self, column: &str) -> Option<&'a common::types::Value> {
+        self.row.values.get(column_index(self.column_names, column)?)
+    }
+
+    /// Whether there is the column
+    #[inline]
+    pub fn contains(&self, column: &str) -> bool {
+        self.get(column).is_some()
+    }
+
+    /// Get the value by column name and convert it
+    /// 按列名获取值并转换类型
+    pub fn get_as<T>(&self, column: &str) -> crate::Result<T>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+    {
+        let value = self
+            .get(column)
+            .ok_or_else(|| crate::Error::ColumnNotFound(column.to_string()))?;
+        convert(column, value.clone())
+    }
+
+    /// Get the value by column name and convert it, `None` if the column is missing or null
+    /// 按列名获取值并转换类型，列不存在或为空值时返回 `None`
+    pub fn get_opt<T>(&self, column: &str) -> crate::Result<Option<T>>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+    {
+        match self.get(column) {
+            Some(value) if !value.is_null() => convert(column, value.clone()).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get the value by column name and convert it, call `f` if the column is missing or null
+    /// 按列名获取值并转换类型，列不存在或为空值时调用 `f`
+    pub fn get_or_else<T, F>(&self, column: &str, f: F) -> crate::Result<T>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+        F: FnOnce() -> T,
+    {
+        Ok(self.get_opt(column)?.unwrap_or_else(f))
+    }
+
+    /// Get the value by index of column
+    #[inline]
+    pub fn get_by_index(&self, index: usize) -> Option<&'a common::types::Value> {
+        self.row.values.get(index)
+    }
+
+    /// Get all values in order of columns
+    #[inline]
+    pub fn values(&self) -> &'a [common::types::Value] {
+        &self.row.values
+    }
+
+    /// Get count of values
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.row.values.len()
+    }
+
+    /// Whether there is no value
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// One row of result set owning its values, which could be taken out by column name
+/// 拥有其值的结果集中的一行，可以按列名取出值
+#[derive(Debug, Clone)]
+pub struct OwnedRecord<'a> {
+    column_names: &'a [std::vec::Vec<u8>],
+    row: common::types::Row,
+}
+
+impl<'a> OwnedRecord<'a> {
+    fn new(column_names: &'a [std::vec::Vec<u8>], row: common::types::Row) -> Self {
+        OwnedRecord { column_names, row }
+    }
+
+    /// Borrow as `Record`
+    #[inline]
+    pub fn as_record(&self) -> Record<'_> {
+        Record::new(self.column_names, &self.row)
+    }
+
+    /// Take the value by column name, it's left null in the row
+    /// 按列名取出值，行中留下空值
+    pub fn take(&mut self, column: &str) -> Option<common::types::Value> {
+        let value = self
+            .row
+            .values
+            .get_mut(column_index(self.column_names, column)?)?;
+        Some(std::mem::replace(
+            value,
+            common::types::Value::nVal(common::types::NullType::__NULL__),
+        ))
+    }
+
+    /// Take the value by column name and convert it, like `Record::get_as`
+    /// 按列名取出值并转换类型
+    pub fn take_as<T>(&mut self, column: &str) -> crate::Result<T>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+    {
+        let value = self
+            .take(column)
+            .ok_or_else(|| crate::Error::ColumnNotFound(column.to_string()))?;
+        convert(column, value)
+    }
+
+    /// Take the value by column name and convert it, like `Record::get_opt`
+    /// 按列名取出值并转换类型，列不存在或为空值时返回 `None`
+    pub fn take_opt<T>(&mut self, column: &str) -> crate::Result<Option<T>>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+    {
+        match self.take(column) {
+            Some(value) if !value.is_null() => convert(column, value).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Take the value by column name and convert it, like `Record::get_or_else`
+    /// 按列名取出值并转换类型，列不存在或为空值时调用 `f`
+    pub fn take_or_else<T, F>(&mut self, column: &str, f: F) -> crate::Result<T>
+    where
+        T: TryFrom<common::types::Value, Error = ConvertError>,
+        F: FnOnce() -> T,
+    {
+        Ok(self.take_opt(column)?.unwrap_or_else(f))
+    }
+
+    /// Take all values in order of columns
+    #[inline]
+    pub fn into_values(self) -> std::vec::Vec<common::types::Value> {
+        self.row.values
+    }
+}
+
+/// The names not in UTF-8 are converted lossily
+fn column_names(names: &[std::vec::Vec<u8>]) -> std::vec::Vec<std::borrow::Cow<'_, str>> {
+    names.iter().map(|name| String::from_utf8_lossy(name)).collect()
+}
+
+#[inline]
+fn column_index(names: &[std::vec::Vec<u8>], column: &str) -> Option<usize> {
+    names.iter().position(|name| name == column.as_bytes())
+}
+
+#[inline]
+fn convert<T>(column: &str, value: common::types::Value) -> crate::Result<T>
+where
+    T: TryFrom<common::types::Value, Error = ConvertError>,
+{
+    T::try_from(value).map_err(|error| crate::Error::Convert {
+        column: column.to_string(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_set(column_names: std::vec::Vec<std::vec::Vec<u8>>) -> ResultSet {
+        ResultSet::new(graph::types::ExecutionResponse {
+            error_code: common::types::ErrorCode::SUCCEEDED,
+            latency_in_us: 0,
+            data: Some(common::types::DataSet {
+                column_names,
+                rows: vec![common::types::Row {
+                    values: vec![
+                        common::types::Value::sVal(b"a".to_vec()),
+                        common::types::Value::iVal(1),
+                    ],
+                }],
+            }),
+            space_name: None,
+            error_msg: None,
+            plan_desc: None,
+            comment: None,
+        })
+    }
+
+    #[test]
+    fn test_column_names() {
+        let result = result_set(vec![b"name".to_vec(), b"a\xffb".to_vec()]);
+        assert_eq!(result.column_names(), vec!["name", "a\u{fffd}b"]);
+        let record = result.row(0).unwrap();
+        assert_eq!(record.column_names(), result.column_names());
+        assert!(result.row(1).is_none());
+    }
+
+    #[test]
+    fn test_take() {
+        let result = result_set(vec![b"name".to_vec(), b"age".to_vec()]);
+        let data = result.data().unwrap();
+        let mut record = OwnedRecord::new(&data.column_names, data.rows[0].clone());
+        assert_eq!(record.take_as::<String>("name").unwrap(), "a");
+        assert_eq!(record.take_opt::<String>("name").unwrap(), None);
+        assert_eq!(record.take_or_else("age", || 0i64).unwrap(), 1);
+        assert!(record.as_record().get("age").unwrap().is_null());
+        assert!(matches!(
+            record.take_as::<i64>("none"),
+            Err(crate::Error::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_get() {
+        let result = result_set(vec![b"name".to_vec(), b"age".to_vec()]);
+        assert_eq!(result.get(0, "age"), Some(&common::types::Value::iVal(1)));
+        assert_eq!(result.get(0, "none"), None);
+        assert_eq!(result.get(1, "age"), None);
+        let record = result.row(0).unwrap();
+        assert_eq!(record.get_as::<String>("name").unwrap(), "a");
+        assert_eq!(record.get_opt::<i64>("none").unwrap(), None);
+        assert!(matches!(
+            record.get_as::<i64>("none"),
+            Err(crate::Error::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            record.get_as::<i64>("name"),
+            Err(crate::Error::Convert { column, .. }) if column == "name"
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+//! Check the quoted names and strings against a NebulaGraph server, run by
+//! `NEBULA_TEST_ADDRESS=root:nebula@127.0.0.1:9669 cargo test --test escape -- --ignored`
+
+use nebula_rust::common::types::Value;
+use nebula_rust::graph_client::connection_pool::ConnectionPool_nebula;
+use nebula_rust::graph_client::escape;
+use nebula_rust::graph_client::nebula_schema::{self, ColType, DataType, Tag, VertexRow};
+use nebula_rust::graph_client::query::{self, Fetch, Sentence, Source};
+
+/// Retry until it succeeds, the new space and schema take effect after the heartbeat
+async fn until_ok<T, F, Fut>(mut f: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = nebula_rust::Result<T>>,
+{
+    for _ in 0..30 {
+        if let Ok(value) = f().await {
+            return value;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    f().await.unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_quoted_names_on_server() {
+    let address = std::env::var("NEBULA_TEST_ADDRESS")
+        .unwrap_or_else(|_| String::from("root:nebula@127.0.0.1:9669"));
+    let pool = ConnectionPool_nebula::new_pool(&address).unwrap();
+    let session = pool.get_session(true).await.unwrap();
+
+    let space = "escape test-空间\\;\"'";
+    let tag_name = "tag\\ name;\"";
+    let prop = "prop-名\\";
+    let vid = "v\"1\\";
+    let value = "va\"l`ue\\\n'";
+
+    session.create_space(space, 1, 1, true, 32, "").await.unwrap();
+    until_ok(|| session.switch_space(space)).await;
+    let tags = [Tag::new(prop, DataType::String, true, "", "")];
+    let create = nebula_schema::create_schema_statement(ColType::Tag, tag_name, "", &tags, None).unwrap();
+    session.query_in_space(space, &create).await.unwrap();
+
+    let row = VertexRow {
+        vid: Value::from(vid),
+        values: vec![Value::from(value)],
+    };
+    let insert = nebula_schema::insert_vertex_statement(tag_name, &[prop], &[row]).unwrap();
+    until_ok(|| session.query_in_space(space, &insert)).await;
+
+    let mut fetch = Fetch::vertices(&[tag_name], Source::vids([vid]));
+    fetch.yield_as(&query::prop(&[tag_name, prop]).unwrap(), "p");
+    let result = session
+        .query_in_space(space, &fetch.statement().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(result.row(0).unwrap().get_as::<String>("p").unwrap(), value);
+
+    let drop = format!("DROP SPACE {};", escape::quote_identifier(space).unwrap());
+    session.query(&drop).await.unwrap();
+}
@@ -0,0 +1,113 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use nebula_rust::common::types::{DataSet, ErrorCode, NullType, Row, Value};
+use nebula_rust::value::from_row::FromRow;
+use nebula_rust::value::result_set::ResultSet;
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Player {
+    name: String,
+    #[nebula(rename = "player_age")]
+    age: i8,
+    #[nebula(optional)]
+    team: Option<String>,
+    #[nebula(default)]
+    tags: Vec<String>,
+    #[nebula(default = "unknown")]
+    country: String,
+    #[nebula(skip)]
+    score: f64,
+}
+
+fn unknown() -> String {
+    String::from("unknown")
+}
+
+fn result_set(rows: Vec<Vec<Value>>) -> ResultSet {
+    ResultSet::new(graph::types::ExecutionResponse {
+        error_code: ErrorCode::SUCCEEDED,
+        latency_in_us: 0,
+        data: Some(DataSet {
+            column_names: ["name", "player_age", "team", "tags"]
+                .iter()
+                .map(|name| name.as_bytes().to_vec())
+                .collect(),
+            rows: rows.into_iter().map(|values| Row { values }).collect(),
+        }),
+        space_name: None,
+        error_msg: None,
+        plan_desc: None,
+        comment: None,
+    })
+}
+
+#[test]
+fn test_from_row() {
+    let result = result_set(vec![
+        vec![
+            Value::from("Tim"),
+            Value::from(42),
+            Value::from("Spurs"),
+            Value::from(vec!["a", "b"]),
+        ],
+        vec![
+            Value::from("Tony"),
+            Value::from(36),
+            Value::nVal(NullType::__NULL__),
+            Value::nVal(NullType::__NULL__),
+        ],
+    ]);
+    let expected = vec![
+        Player {
+            name: String::from("Tim"),
+            age: 42,
+            team: Some(String::from("Spurs")),
+            tags: vec![String::from("a"), String::from("b")],
+            country: unknown(),
+            score: 0.0,
+        },
+        Player {
+            name: String::from("Tony"),
+            age: 36,
+            team: None,
+            tags: vec![],
+            country: unknown(),
+            score: 0.0,
+        },
+    ];
+    let borrowed = result
+        .iter()
+        .map(|record| Player::from_record(&record))
+        .collect::<nebula_rust::Result<Vec<Player>>>()
+        .unwrap();
+    assert_eq!(borrowed, expected);
+    assert_eq!(result.into_typed::<Player>().unwrap(), expected);
+}
+
+#[test]
+fn test_from_row_error() {
+    let result = result_set(vec![vec![
+        Value::from("Tim"),
+        Value::from(420),
+        Value::from("Spurs"),
+        Value::from(vec!["a"]),
+    ]]);
+    match result.clone().into_typed::<Player>() {
+        Err(nebula_rust::Error::Convert { column, .. }) => assert_eq!(column, "player_age"),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        Player::from_record(&result.row(0).unwrap()),
+        Err(nebula_rust::Error::Convert { .. })
+    ));
+
+    let result = result_set(vec![vec![Value::from("Tim")]]);
+    match result.into_typed::<Player>() {
+        Err(nebula_rust::Error::ColumnNotFound(column)) => assert_eq!(column, "player_age"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
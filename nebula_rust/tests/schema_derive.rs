@@ -0,0 +1,57 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use nebula_rust::graph_client::nebula_schema::NebulaTag;
+use nebula_rust::value::from_row::FromRow;
+
+#[derive(NebulaTag)]
+#[nebula(name = "place", ttl_duration = 3600, ttl_col = "created")]
+struct Place {
+    #[nebula(vid)]
+    id: String,
+    #[nebula(data_type = "Geography(Point)", default_value = "POINT(0 0)")]
+    location: String,
+    #[nebula(data_type = "Geography")]
+    area: Option<String>,
+    #[nebula(data_type = "Duration", default_value = "{days: 1}")]
+    stay: Option<String>,
+    #[nebula(data_type = "Timestamp", default_value = "now()")]
+    created: i64,
+}
+
+// Each derive skips the attributes of the other one
+#[derive(FromRow, NebulaTag)]
+struct Player {
+    #[nebula(vid)]
+    name: String,
+    #[nebula(data_type = "Int16", default_value = 0)]
+    age: i64,
+    #[nebula(optional)]
+    team: Option<String>,
+}
+
+#[test]
+fn test_create_statement() {
+    assert_eq!(
+        Place::create_statement().unwrap(),
+        "CREATE tag IF NOT EXISTS `place` (\
+         `location` geography(point) NOT NULL DEFAULT ST_GeogFromText(\"POINT(0 0)\") ,\
+         `area` geography NULL ,\
+         `stay` duration NULL DEFAULT duration({days: 1}) ,\
+         `created` timestamp NOT NULL DEFAULT now() ) \
+         TTL_DURATION = 3600, TTL_COL = \"created\";"
+    );
+}
+
+#[test]
+fn test_derive_with_from_row() {
+    assert_eq!(
+        Player::create_statement().unwrap(),
+        "CREATE tag IF NOT EXISTS `player` (\
+         `age` int16 NOT NULL DEFAULT 0 ,\
+         `team` string NULL );"
+    );
+}
@@ -0,0 +1,220 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use syn::{DeriveInput, Field, Lit, LitStr, Token};
+
+/// How to fill the field when the column is missing or null
+pub enum DefaultValue {
+    /// `#[nebula(default)]`
+    Trait,
+    /// `#[nebula(default = "path::to::fn")]`
+    Function(syn::ExprPath),
+}
+
+/// The field attributes accepted by `FromRow`
+pub const FROM_ROW_ATTRS: &[&str] = &["rename", "optional", "default", "skip"];
+
+/// The field attributes accepted by `NebulaTag` and `NebulaEdge`
+pub const SCHEMA_ATTRS: &[&str] = &[
+    "rename",
+    "skip",
+    "vid",
+    "src",
+    "dst",
+    "rank",
+    "data_type",
+    "default_value",
+    "comment",
+];
+
+/// The `#[nebula(..)]` attributes of one field, shared by all derives
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub rename: Option<String>,
+    pub optional: bool,
+    pub default: Option<DefaultValue>,
+    /// Not a column or property
+    pub skip: bool,
+    /// The vid of vertex
+    pub vid: bool,
+    /// The source vid of edge
+    pub src: bool,
+    /// The destination vid of edge
+    pub dst: bool,
+    /// The rank of edge
+    pub rank: bool,
+    /// The variant of `DataType`, e.g. `"Int32"`
+    pub data_type: Option<syn::Expr>,
+    /// The default value of property in schema
+    pub default_value: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl FieldAttrs {
+    /// Parse the attributes in `allowed` of the derive. The ones of other derives are skipped,
+    /// so one struct could derive several of them, fail if no derive knows the attribute
+    pub fn parse(field: &Field, derive: &str, allowed: &[&str]) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("nebula")) {
+            attr.parse_nested_meta(|meta| {
+                let is = |names: &[&str]| names.iter().any(|name| meta.path.is_ident(name));
+                if !is(allowed) {
+                    if !is(FROM_ROW_ATTRS) && !is(SCHEMA_ATTRS) {
+                        return Err(meta.error(format!("unsupported nebula attribute for {}", derive)));
+                    }
+                    if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<Lit>()?;
+                    }
+                    return Ok(());
+                }
+                if meta.path.is_ident("rename") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.rename = Some(name.value());
+                } else if meta.path.is_ident("optional") {
+                    attrs.optional = true;
+                } else if meta.path.is_ident("default") {
+                    attrs.default = Some(if meta.input.peek(Token![=]) {
+                        let path: LitStr = meta.value()?.parse()?;
+                        DefaultValue::Function(path.parse()?)
+                    } else {
+                        DefaultValue::Trait
+                    });
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("vid") {
+                    attrs.vid = true;
+                } else if meta.path.is_ident("src") {
+                    attrs.src = true;
+                } else if meta.path.is_ident("dst") {
+                    attrs.dst = true;
+                } else if meta.path.is_ident("rank") {
+                    attrs.rank = true;
+                } else if meta.path.is_ident("data_type") {
+                    let data_type: LitStr = meta.value()?.parse()?;
+                    attrs.data_type = Some(data_type.parse()?);
+                } else if meta.path.is_ident("default_value") {
+                    attrs.default_value = Some(match meta.value()?.parse()? {
+                        Lit::Str(s) => s.value(),
+                        Lit::Int(i) => i.base10_digits().to_string(),
+                        Lit::Float(f) => f.base10_digits().to_string(),
+                        Lit::Bool(b) => b.value.to_string(),
+                        lit => return Err(syn::Error::new_spanned(lit, "unsupported default value")),
+                    });
+                } else if meta.path.is_ident("comment") {
+                    let comment: LitStr = meta.value()?.parse()?;
+                    attrs.comment = Some(comment.value());
+                }
+                Ok(())
+            })?;
+        }
+        if attrs.optional && attrs.default.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`optional` and `default` can't be used together",
+            ));
+        }
+        Ok(attrs)
+    }
+
+    /// The column or property name of the field
+    pub fn name(&self, field: &Field) -> String {
+        match &self.rename {
+            Some(name) => name.clone(),
+            None => field.ident.as_ref().unwrap().to_string(),
+        }
+    }
+
+    /// Whether it's the vid, src, dst or rank instead of property
+    pub fn is_key(&self) -> bool {
+        self.vid || self.src || self.dst || self.rank
+    }
+}
+
+/// The `#[nebula(..)]` attributes of the struct
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// The name of tag or edge
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    /// Seconds to live of `TTL_DURATION`
+    pub ttl_duration: Option<syn::LitInt>,
+    /// The property of `TTL_COL`
+    pub ttl_col: Option<String>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = ContainerAttrs::default();
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("nebula")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.name = Some(name.value());
+                } else if meta.path.is_ident("comment") {
+                    let comment: LitStr = meta.value()?.parse()?;
+                    attrs.comment = Some(comment.value());
+                } else if meta.path.is_ident("ttl_duration") {
+                    attrs.ttl_duration = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ttl_col") {
+                    let ttl_col: LitStr = meta.value()?.parse()?;
+                    attrs.ttl_col = Some(ttl_col.value());
+                } else {
+                    return Err(meta.error("unsupported nebula attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        if attrs.ttl_duration.is_some() != attrs.ttl_col.is_some() {
+            return Err(syn::Error::new_spanned(
+                input,
+                "`ttl_duration` and `ttl_col` must be used together",
+            ));
+        }
+        Ok(attrs)
+    }
+
+    /// The name of tag or edge, the struct name in snake case by default
+    pub fn name(&self, input: &DeriveInput) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => snake_case(&input.ident.to_string()),
+        }
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let chars: Vec<char> = name.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lower = i > 0 && !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let before_lower = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || before_lower {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(*c);
+        }
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("Player"), "player");
+        assert_eq!(snake_case("FollowEdge"), "follow_edge");
+        assert_eq!(snake_case("HTTPServer"), "http_server");
+        assert_eq!(snake_case("Player2Team"), "player2_team");
+        assert_eq!(snake_case("Already_Snake"), "already_snake");
+    }
+}
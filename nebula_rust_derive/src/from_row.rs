@@ -0,0 +1,197 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::attr::{DefaultValue, FieldAttrs, FROM_ROW_ATTRS};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "FromRow only supports struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "FromRow only supports struct with named fields",
+            ))
+        }
+    };
+
+    // The values are borrowed from `Record` and taken out of `OwnedRecord`
+    let mut inits = Vec::with_capacity(fields.len());
+    let mut owned_inits = Vec::with_capacity(fields.len());
+    let mut columns = std::collections::HashSet::new();
+    for field in fields {
+        let attrs = FieldAttrs::parse(field, "FromRow", FROM_ROW_ATTRS)?;
+        let ident = &field.ident;
+        if attrs.skip {
+            inits.push(quote!(#ident: ::std::default::Default::default()));
+            owned_inits.push(quote!(#ident: ::std::default::Default::default()));
+            continue;
+        }
+        let column = attrs.name(field);
+        if !columns.insert(column.clone()) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("the column `{}` is read by more than one field", column),
+            ));
+        }
+        let (get, owned_get) = if attrs.optional {
+            (quote!(record.get_opt(#column)?), quote!(record.take_opt(#column)?))
+        } else {
+            match &attrs.default {
+                Some(DefaultValue::Trait) => (
+                    quote!(record.get_or_else(#column, ::std::default::Default::default)?),
+                    quote!(record.take_or_else(#column, ::std::default::Default::default)?),
+                ),
+                Some(DefaultValue::Function(path)) => (
+                    quote!(record.get_or_else(#column, #path)?),
+                    quote!(record.take_or_else(#column, #path)?),
+                ),
+                None => (quote!(record.get_as(#column)?), quote!(record.take_as(#column)?)),
+            }
+        };
+        inits.push(quote!(#ident: #get));
+        owned_inits.push(quote!(#ident: #owned_get));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::nebula_rust::value::from_row::FromRow for #name #ty_generics #where_clause {
+            fn from_record(
+                record: &::nebula_rust::value::result_set::Record<'_>,
+            ) -> ::nebula_rust::Result<Self> {
+                ::std::result::Result::Ok(#name {
+                    #(#inits,)*
+                })
+            }
+
+            #[allow(unused_mut)]
+            fn from_owned_record(
+                mut record: ::nebula_rust::value::result_set::OwnedRecord<'_>,
+            ) -> ::nebula_rust::Result<Self> {
+                ::std::result::Result::Ok(#name {
+                    #(#owned_inits,)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(input: DeriveInput) -> String {
+        expand(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_expand() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Player {
+                name: String,
+                #[nebula(rename = "player_age")]
+                age: i64,
+                #[nebula(optional)]
+                team: Option<String>,
+                #[nebula(default = "default_tags")]
+                tags: Vec<String>,
+                #[nebula(skip)]
+                score: f64,
+            }
+        };
+        let expected = quote! {
+            impl ::nebula_rust::value::from_row::FromRow for Player {
+                fn from_record(
+                    record: &::nebula_rust::value::result_set::Record<'_>,
+                ) -> ::nebula_rust::Result<Self> {
+                    ::std::result::Result::Ok(Player {
+                        name: record.get_as("name")?,
+                        age: record.get_as("player_age")?,
+                        team: record.get_opt("team")?,
+                        tags: record.get_or_else("tags", default_tags)?,
+                        score: ::std::default::Default::default(),
+                    })
+                }
+
+                #[allow(unused_mut)]
+                fn from_owned_record(
+                    mut record: ::nebula_rust::value::result_set::OwnedRecord<'_>,
+                ) -> ::nebula_rust::Result<Self> {
+                    ::std::result::Result::Ok(Player {
+                        name: record.take_as("name")?,
+                        age: record.take_as("player_age")?,
+                        team: record.take_opt("team")?,
+                        tags: record.take_or_else("tags", default_tags)?,
+                        score: ::std::default::Default::default(),
+                    })
+                }
+            }
+        };
+        assert_eq!(expand(&input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_skip_schema_attrs() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct A {
+                #[nebula(vid)]
+                id: String,
+                #[nebula(data_type = "Int32", default_value = 0, comment = "age")]
+                age: i32,
+            }
+        };
+        let plain: DeriveInput = syn::parse_quote! {
+            struct A {
+                id: String,
+                age: i32,
+            }
+        };
+        assert_eq!(
+            expand(&input).unwrap().to_string(),
+            expand(&plain).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_reject_unknown_attrs() {
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(unknown)] age: i32 })),
+            "unsupported nebula attribute for FromRow"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(vid, unknown = 1)] id: String })),
+            "unsupported nebula attribute for FromRow"
+        );
+    }
+
+    #[test]
+    fn test_reject() {
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(optional, default)] a: Option<i64> })),
+            "`optional` and `default` can't be used together"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { a: i64, #[nebula(rename = "a")] b: i64 })),
+            "the column `a` is read by more than one field"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A(i64);)),
+            "FromRow only supports struct with named fields"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+//! The derive macros re-exported by `nebula-rust`
+//! 由 `nebula-rust` 重新导出的派生宏
+
+mod attr;
+mod from_row;
+mod schema;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derive `nebula_rust::value::from_row::FromRow` for the struct with named fields,
+/// each field is converted from the column of the same name.
+///
+/// Field attributes:
+/// - `#[nebula(rename = "col")]` read the column `col` instead
+/// - `#[nebula(optional)]` the field is `Option<T>`, `None` if the column is missing or null
+/// - `#[nebula(default)]` or `#[nebula(default = "path::to::fn")]` use `Default::default()`
+///   or the function if the column is missing or null
+/// - `#[nebula(skip)]` not a column, filled by `Default::default()`
+///
+/// The values are moved out of rows by `ResultSet::into_typed`, and each column could be read
+/// by only one field
+///
+/// 为具名字段的结构体派生 `FromRow`，每个字段由同名列转换而来
+#[proc_macro_derive(FromRow, attributes(nebula))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_row::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `nebula_rust::graph_client::nebula_schema::NebulaTag` for the struct with named fields,
+/// the tag is named after the struct in snake case and each field is a property.
+///
+/// Struct attributes: `#[nebula(name = "tag", comment = "..")]`, and
+/// `#[nebula(ttl_duration = 3600, ttl_col = "prop")]` for the time to live
+///
+/// Field attributes:
+/// - `#[nebula(vid)]` the vid of vertex, required
+/// - `#[nebula(rename = "prop")]` name of property
+/// - `#[nebula(data_type = "Int32")]` the variant of `DataType`, e.g. `"FixedString(32)"` or
+///   `"Geography(Point)"`, inferred from the rust type by default, `Option<T>` is nullable
+/// - `#[nebula(default_value = 0, comment = "..")]` the default value and comment in schema
+/// - `#[nebula(skip)]` not a property
+///
+/// The attributes of `FromRow` like `optional` are rejected
+///
+/// 为具名字段的结构体派生 `NebulaTag`，标签名为结构体名的蛇形命名，每个字段为一个属性
+#[proc_macro_derive(NebulaTag, attributes(nebula))]
+pub fn derive_nebula_tag(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    schema::expand(&input, schema::Kind::Tag)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `nebula_rust::graph_client::nebula_schema::NebulaEdge` for the struct with named fields,
+/// the attributes are the same as `NebulaTag` except the keys of edge:
+/// `#[nebula(src)]` and `#[nebula(dst)]` are required, `#[nebula(rank)]` is 0 if missing
+///
+/// 为具名字段的结构体派生 `NebulaEdge`，属性与 `NebulaTag` 相同，但需要 `src` 和 `dst` 字段，
+/// 没有 `rank` 字段时为 0
+#[proc_macro_derive(NebulaEdge, attributes(nebula))]
+pub fn derive_nebula_edge(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    schema::expand(&input, schema::Kind::Edge)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,455 @@
+/* Copyright (c) 2021 vesoft inc. All rights reserved.
+ *
+ * This source code is licensed under Apache 2.0 License,
+ * attached with Common Clause Condition 1.0, found in the LICENSES directory.
+ */
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, GenericArgument, PathArguments, Type};
+
+use crate::attr::{ContainerAttrs, FieldAttrs, SCHEMA_ATTRS};
+
+/// Tag or edge
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    Tag,
+    Edge,
+}
+
+impl Kind {
+    fn derive_name(self) -> &'static str {
+        match self {
+            Kind::Tag => "NebulaTag",
+            Kind::Edge => "NebulaEdge",
+        }
+    }
+}
+
+pub fn expand(input: &DeriveInput, kind: Kind) -> syn::Result<TokenStream> {
+    let unsupported = || {
+        syn::Error::new_spanned(
+            input,
+            format!("{} only supports struct with named fields", kind.derive_name()),
+        )
+    };
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(unsupported()),
+        },
+        _ => return Err(unsupported()),
+    };
+    let container = ContainerAttrs::parse(input)?;
+    let schema_name = container.name(input);
+    let comment = container.comment.clone().unwrap_or_default();
+    let schema = quote!(::nebula_rust::graph_client::nebula_schema);
+    let ttl = match (&container.ttl_duration, &container.ttl_col) {
+        (Some(duration), Some(col)) => quote!(::std::option::Option::Some(#schema::Ttl::new(#duration, #col))),
+        _ => quote!(::std::option::Option::None),
+    };
+
+    let mut properties = Vec::new();
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    let (mut vid, mut src, mut dst, mut rank) = (None, None, None, None);
+    for field in fields {
+        let attrs = FieldAttrs::parse(field, kind.derive_name(), SCHEMA_ATTRS)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let value = quote! {
+            ::std::convert::Into::<::nebula_rust::common::types::Value>::into(
+                ::std::clone::Clone::clone(&self.#ident),
+            )
+        };
+        if attrs.is_key() {
+            let keys = [
+                (attrs.vid, Kind::Tag, &mut vid, "vid"),
+                (attrs.src, Kind::Edge, &mut src, "src"),
+                (attrs.dst, Kind::Edge, &mut dst, "dst"),
+                (attrs.rank, Kind::Edge, &mut rank, "rank"),
+            ];
+            for (marked, key_kind, slot, key) in keys {
+                if !marked {
+                    continue;
+                }
+                if key_kind != kind || slot.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        format!("unexpected `{}` field for {}", key, kind.derive_name()),
+                    ));
+                }
+                *slot = Some(if key == "rank" {
+                    quote!(::std::convert::Into::<i64>::into(::std::clone::Clone::clone(&self.#ident)))
+                } else {
+                    value.clone()
+                });
+            }
+            continue;
+        }
+
+        let name = attrs.name(field);
+        let (data_type, nullable) = match &attrs.data_type {
+            Some(data_type) => (data_type_path(data_type)?, option_inner(&field.ty).is_some()),
+            None => data_type_of(field)?,
+        };
+        let default_value = attrs.default_value.clone().unwrap_or_default();
+        let property_comment = attrs.comment.clone().unwrap_or_default();
+        properties.push(quote! {
+            #schema::Tag::new(#name, #data_type, #nullable, #default_value, #property_comment)
+        });
+        names.push(name);
+        values.push(value);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let missing = |key: &str| {
+        syn::Error::new_spanned(
+            input,
+            format!("{} requires a `#[nebula({})]` field", kind.derive_name(), key),
+        )
+    };
+    let body = match kind {
+        Kind::Tag => {
+            let vid = vid.ok_or_else(|| missing("vid"))?;
+            quote! {
+                impl #impl_generics #schema::NebulaTag for #ident #ty_generics #where_clause {
+                    fn tag_name() -> &'static str {
+                        #schema_name
+                    }
+
+                    fn tag_comment() -> &'static str {
+                        #comment
+                    }
+
+                    fn tag_ttl() -> ::std::option::Option<#schema::Ttl> {
+                        #ttl
+                    }
+
+                    fn properties() -> ::std::vec::Vec<#schema::Tag> {
+                        vec![#(#properties),*]
+                    }
+
+                    fn property_names() -> ::std::vec::Vec<&'static str> {
+                        vec![#(#names),*]
+                    }
+
+                    fn vid(&self) -> ::nebula_rust::common::types::Value {
+                        #vid
+                    }
+
+                    fn property_values(&self) -> ::std::vec::Vec<::nebula_rust::common::types::Value> {
+                        vec![#(#values),*]
+                    }
+                }
+            }
+        }
+        Kind::Edge => {
+            let src = src.ok_or_else(|| missing("src"))?;
+            let dst = dst.ok_or_else(|| missing("dst"))?;
+            let rank = rank.map(|rank| {
+                quote! {
+                    fn rank(&self) -> i64 {
+                        #rank
+                    }
+                }
+            });
+            quote! {
+                impl #impl_generics #schema::NebulaEdge for #ident #ty_generics #where_clause {
+                    fn edge_name() -> &'static str {
+                        #schema_name
+                    }
+
+                    fn edge_comment() -> &'static str {
+                        #comment
+                    }
+
+                    fn edge_ttl() -> ::std::option::Option<#schema::Ttl> {
+                        #ttl
+                    }
+
+                    fn properties() -> ::std::vec::Vec<#schema::Tag> {
+                        vec![#(#properties),*]
+                    }
+
+                    fn property_names() -> ::std::vec::Vec<&'static str> {
+                        vec![#(#names),*]
+                    }
+
+                    fn src(&self) -> ::nebula_rust::common::types::Value {
+                        #src
+                    }
+
+                    fn dst(&self) -> ::nebula_rust::common::types::Value {
+                        #dst
+                    }
+
+                    #rank
+
+                    fn property_values(&self) -> ::std::vec::Vec<::nebula_rust::common::types::Value> {
+                        vec![#(#values),*]
+                    }
+                }
+            }
+        }
+    };
+    Ok(body)
+}
+
+/// The inner type if it's `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The fully-qualified `DataType` of `#[nebula(data_type = "..")]`, the shape of geography is a `GeoShape`,
+/// e.g. `Geography(Point)` is `DataType::Geography(GeoShape::Point)`, and `Geography` is any shape
+fn data_type_path(data_type: &syn::Expr) -> syn::Result<TokenStream> {
+    let schema = quote!(::nebula_rust::graph_client::nebula_schema);
+    match data_type {
+        syn::Expr::Path(path) if path.path.is_ident("Geography") => {
+            Ok(quote!(#schema::DataType::Geography(#schema::GeoShape::Any)))
+        }
+        syn::Expr::Call(call) if matches!(&*call.func, syn::Expr::Path(func) if func.path.is_ident("Geography")) => {
+            match call.args.first() {
+                Some(syn::Expr::Path(shape)) if call.args.len() == 1 && shape.path.get_ident().is_some() => {
+                    Ok(quote!(#schema::DataType::Geography(#schema::GeoShape::#shape)))
+                }
+                _ => Err(syn::Error::new_spanned(
+                    data_type,
+                    "the shape of geography should be one of Any, Point, LineString and Polygon",
+                )),
+            }
+        }
+        _ => Ok(quote!(#schema::DataType::#data_type)),
+    }
+}
+
+/// Map the rust type of field to `DataType`, `Option<T>` is nullable
+fn data_type_of(field: &Field) -> syn::Result<(TokenStream, bool)> {
+    let (ty, nullable) = match option_inner(&field.ty) {
+        Some(inner) => (inner, true),
+        None => (&field.ty, false),
+    };
+    let ident = match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let variant = match ident.as_deref() {
+        Some("i64") | Some("u32") => quote!(Int),
+        Some("i32") | Some("u16") => quote!(Int32),
+        Some("i16") => quote!(Int16),
+        Some("i8") => quote!(Int8),
+        Some("f64") => quote!(Double),
+        Some("f32") => quote!(Float),
+        Some("bool") => quote!(Bool),
+        Some("String") | Some("str") => quote!(String),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "can't infer the data type, specify it by `#[nebula(data_type = \"..\")]`",
+            ))
+        }
+    };
+    Ok((
+        quote!(::nebula_rust::graph_client::nebula_schema::DataType::#variant),
+        nullable,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_err(input: DeriveInput, kind: Kind) -> String {
+        expand(&input, kind).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_expand_tag() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[nebula(name = "player", comment = "players", ttl_duration = 100, ttl_col = "created")]
+            struct Player {
+                #[nebula(vid)]
+                id: String,
+                #[nebula(rename = "player_name", comment = "name")]
+                name: String,
+                #[nebula(default_value = 18)]
+                age: Option<i32>,
+                #[nebula(data_type = "FixedString(8)")]
+                code: String,
+                created: i64,
+                #[nebula(skip)]
+                score: f64,
+            }
+        };
+        let expected = quote! {
+            impl ::nebula_rust::graph_client::nebula_schema::NebulaTag for Player {
+                fn tag_name() -> &'static str {
+                    "player"
+                }
+
+                fn tag_comment() -> &'static str {
+                    "players"
+                }
+
+                fn tag_ttl() -> ::std::option::Option<::nebula_rust::graph_client::nebula_schema::Ttl> {
+                    ::std::option::Option::Some(::nebula_rust::graph_client::nebula_schema::Ttl::new(100, "created"))
+                }
+
+                fn properties() -> ::std::vec::Vec<::nebula_rust::graph_client::nebula_schema::Tag> {
+                    vec![
+                        ::nebula_rust::graph_client::nebula_schema::Tag::new("player_name", ::nebula_rust::graph_client::nebula_schema::DataType::String, false, "", "name"),
+                        ::nebula_rust::graph_client::nebula_schema::Tag::new("age", ::nebula_rust::graph_client::nebula_schema::DataType::Int32, true, "18", ""),
+                        ::nebula_rust::graph_client::nebula_schema::Tag::new("code", ::nebula_rust::graph_client::nebula_schema::DataType::FixedString(8), false, "", ""),
+                        ::nebula_rust::graph_client::nebula_schema::Tag::new("created", ::nebula_rust::graph_client::nebula_schema::DataType::Int, false, "", "")
+                    ]
+                }
+
+                fn property_names() -> ::std::vec::Vec<&'static str> {
+                    vec!["player_name", "age", "code", "created"]
+                }
+
+                fn vid(&self) -> ::nebula_rust::common::types::Value {
+                    ::std::convert::Into::<::nebula_rust::common::types::Value>::into(
+                        ::std::clone::Clone::clone(&self.id),
+                    )
+                }
+
+                fn property_values(&self) -> ::std::vec::Vec<::nebula_rust::common::types::Value> {
+                    vec![
+                        ::std::convert::Into::<::nebula_rust::common::types::Value>::into(::std::clone::Clone::clone(&self.name),),
+                        ::std::convert::Into::<::nebula_rust::common::types::Value>::into(::std::clone::Clone::clone(&self.age),),
+                        ::std::convert::Into::<::nebula_rust::common::types::Value>::into(::std::clone::Clone::clone(&self.code),),
+                        ::std::convert::Into::<::nebula_rust::common::types::Value>::into(::std::clone::Clone::clone(&self.created),)
+                    ]
+                }
+            }
+        };
+        assert_eq!(expand(&input, Kind::Tag).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_edge() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct FollowEdge {
+                #[nebula(src)]
+                from: String,
+                #[nebula(dst)]
+                to: String,
+                #[nebula(rank)]
+                rank: i32,
+                degree: i64,
+            }
+        };
+        let expanded = expand(&input, Kind::Edge).unwrap().to_string();
+        let schema = quote!(::nebula_rust::graph_client::nebula_schema).to_string();
+        assert!(expanded.starts_with(&format!("impl {} :: NebulaEdge for FollowEdge", schema)));
+        assert!(expanded.contains(&quote!(fn edge_name() -> &'static str { "follow_edge" }).to_string()));
+        assert!(expanded.contains(&quote!(fn edge_ttl() -> ::std::option::Option<::nebula_rust::graph_client::nebula_schema::Ttl> {
+            ::std::option::Option::None
+        }).to_string()));
+        assert!(expanded.contains(&quote!(fn rank(&self) -> i64 {
+            ::std::convert::Into::<i64>::into(::std::clone::Clone::clone(&self.rank))
+        }).to_string()));
+        assert!(expanded.contains(&quote!(fn property_names() -> ::std::vec::Vec<&'static str> { vec!["degree"] }).to_string()));
+    }
+
+    #[test]
+    fn test_data_type_path() {
+        let path = |data_type: &str| data_type_path(&syn::parse_str(data_type).unwrap()).map(|path| path.to_string());
+        let schema = quote!(::nebula_rust::graph_client::nebula_schema);
+        assert_eq!(path("FixedString(8)").unwrap(), quote!(#schema::DataType::FixedString(8)).to_string());
+        assert_eq!(path("Duration").unwrap(), quote!(#schema::DataType::Duration).to_string());
+        assert_eq!(
+            path("Geography(Point)").unwrap(),
+            quote!(#schema::DataType::Geography(#schema::GeoShape::Point)).to_string()
+        );
+        assert_eq!(
+            path("Geography").unwrap(),
+            quote!(#schema::DataType::Geography(#schema::GeoShape::Any)).to_string()
+        );
+        assert_eq!(
+            path("Geography(\"point\")").unwrap_err().to_string(),
+            "the shape of geography should be one of Any, Point, LineString and Polygon"
+        );
+        assert!(path("Geography(Point, Polygon)").is_err());
+    }
+
+    #[test]
+    fn test_skip_from_row_attrs() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct A {
+                #[nebula(vid)]
+                id: String,
+                #[nebula(optional)]
+                team: Option<String>,
+                #[nebula(default = "default_age")]
+                age: i64,
+            }
+        };
+        let plain: DeriveInput = syn::parse_quote! {
+            struct A {
+                #[nebula(vid)]
+                id: String,
+                team: Option<String>,
+                age: i64,
+            }
+        };
+        assert_eq!(
+            expand(&input, Kind::Tag).unwrap().to_string(),
+            expand(&plain, Kind::Tag).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_reject() {
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { name: String }), Kind::Tag),
+            "NebulaTag requires a `#[nebula(vid)]` field"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(src)] a: String, b: String }), Kind::Edge),
+            "NebulaEdge requires a `#[nebula(dst)]` field"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(vid)] id: String, #[nebula(src)] a: String }), Kind::Tag),
+            "unexpected `src` field for NebulaTag"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(vid)] id: String, #[nebula(unknown)] a: Option<String> }), Kind::Tag),
+            "unsupported nebula attribute for NebulaTag"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(src)] a: String, #[nebula(dst)] b: String, #[nebula(default, unknown = 1)] c: i64 }), Kind::Edge),
+            "unsupported nebula attribute for NebulaEdge"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(struct A { #[nebula(vid)] id: String, a: Vec<u8> }), Kind::Tag),
+            "can't infer the data type, specify it by `#[nebula(data_type = \"..\")]`"
+        );
+        assert_eq!(
+            expand_err(syn::parse_quote!(#[nebula(ttl_duration = 1)] struct A { #[nebula(vid)] id: String }), Kind::Tag),
+            "`ttl_duration` and `ttl_col` must be used together"
+        );
+    }
+}